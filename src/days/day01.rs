@@ -1,6 +1,5 @@
-use std::str::FromStr;
 use crate::days::Day;
-use crate::util::parser::Parser;
+use crate::util::layout::layout;
 
 pub const DAY1: Day = Day {
     puzzle1,
@@ -67,17 +66,7 @@ enum Direction {
     Left, Right
 }
 
-impl FromStr for Direction {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "L" => Ok(Direction::Left),
-            "R" => Ok(Direction::Right),
-            _ => Err(format!("Unknown direction: {}", s))
-        }
-    }
-}
+layout!(enum Direction { Left = "L", Right = "R" });
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 struct Rotation {
@@ -85,17 +74,10 @@ struct Rotation {
     amount: usize
 }
 
-fn parse_input(input: &str) -> Result<Vec<Rotation>, String> {
-    input.lines().map(|l| parse_line(l)).collect()
-}
+layout!(Rotation { direction, amount });
 
-fn parse_line(input: &str) -> Result<Rotation, String> {
-    let mut parser = Parser::new(input);
-    let dir = parser.one_of(vec!["L", "R"])?;
-    let amount =  parser.usize()?;
-    parser.ensure_exhausted()?;
-
-    Ok(Rotation { amount, direction: dir.parse()? })
+fn parse_input(input: &str) -> Result<Vec<Rotation>, String> {
+    input.lines().map(|l| l.parse()).collect()
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 use std::ops::{RangeInclusive};
 use crate::days::Day;
+use crate::util::number::{digit_count, divisors, repeat_digits};
 use crate::util::parser::Parser;
 
 pub const DAY2: Day = Day {
@@ -39,50 +40,40 @@ fn sum_invalid_ids(ranges: &Vec<RangeInclusive<usize>>, check: &dyn Fn(usize) ->
 fn is_invalid_id(id: usize) -> bool {
     // id is invalid if, when split in the middle, yields two the same values.
     // e.g.: 55, 1010, 123123, 5588055880, etc.
-    let str = id.to_string();
-    if (str.len() % 2) == 1 {
-        // uneven amount of digits is not invalid
-        false
-    } else {
-        let (first, second) = str.split_at(str.len() / 2);
-        first == second
-    }
+    let len = digit_count(id, 10);
+
+    // uneven amount of digits is not invalid
+    len.is_multiple_of(2) && is_repetition(id, len, len / 2)
 }
 
 fn is_invalid_id_p2(id: usize) -> bool {
     // For part 2, and ID is invalid if it is a repetition of any amount.
     // i.e. 123123 (123 x 2), 121212 (12 x 3), etc.
+    // The repeated pattern can only have a length that divides the amount of digits.
+    let len = digit_count(id, 10);
 
-    let str = id.to_string();
-    let max_len = str.len() / 2;
-
-    'no_match: for i in 1..=max_len {
-        if (str.len() % i) != 0 { continue; } // Cannot chunk with this length
-
-        let pattern = &str[0..i];
-        for j in (i..str.len()).step_by(i) {
-            let check = &str[j..j+i];
-            if pattern != check { continue 'no_match }
-        }
-
-        return true;
-    }
+    divisors(len as u64)
+        .map(|d| d as u32)
+        .filter(|pattern_len| *pattern_len < len)
+        .any(|pattern_len| is_repetition(id, len, pattern_len))
+}
 
-    false
+fn is_repetition(id: usize, len: u32, pattern_len: u32) -> bool {
+    // The pattern are the leading digits, which can't start with a 0, so repeating it gives the full length.
+    let pattern = id / 10usize.pow(len - pattern_len);
+    repeat_digits(pattern, len / pattern_len, 10) == id
 }
 
 fn parse_input(input: &str) -> Result<Vec<RangeInclusive<usize>>, String> {
-    let mut result = vec![];
     let mut parser = Parser::new(input);
 
-    while !parser.is_exhausted() {
-        if !result.is_empty() { parser.literal(",")?; }
-
-        let start = parser.usize()?;
-        parser.literal("-")?;
-        let end = parser.usize()?;
-        result.push(start..=end);
-    }
+    let result = parser.sep_by(|p| {
+        let start = p.usize()?;
+        p.literal("-")?;
+        let end = p.usize()?;
+        Ok(start..=end)
+    }, |p| p.literal(","))?;
+    parser.ensure_exhausted()?;
 
     Ok(result)
 }
@@ -1,6 +1,6 @@
-use std::str::FromStr;
 use crate::days::Day;
-use crate::util::number::parse_usize;
+use crate::util::layout::{layout, Digits};
+use crate::util::number::from_digits;
 
 pub const DAY3: Day = Day {
     puzzle1,
@@ -43,7 +43,7 @@ impl BatteryBank {
             }
         }
 
-        first * 10 + second
+        from_digits([first, second], 10)
     }
 
     fn get_overcharge_joulage(&self) -> usize {
@@ -66,24 +66,11 @@ impl BatteryBank {
             }
         }
 
-        // Build the resulting value
-        let mut result = 0;
-        for i in 0..values.len() {
-            result *= 10;
-            result += values[i];
-        }
-
-        result
+        from_digits(values, 10)
     }
 }
 
-impl FromStr for BatteryBank {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self { values: s.chars().map(|c| parse_usize(&c.to_string())).collect::<Result<Vec<_>, _>>()? })
-    }
-}
+layout!(BatteryBank { values as Digits });
 
 fn parse_input(input: &str) -> Result<Vec<BatteryBank>, String> {
     input.lines().map(|l| l.parse::<BatteryBank>()).collect()
@@ -2,8 +2,7 @@ use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::ops::RangeInclusive;
 use crate::days::Day;
-use crate::util::number::parse_usize;
-use crate::util::parser::Parser;
+use crate::util::input::sections;
 
 pub const DAY5: Day = Day {
     puzzle1,
@@ -91,21 +90,16 @@ impl PartialOrd for Delimiter<'_> {
 }
 
 fn parse_input(input: &str) -> Result<InventoryManagement, String> {
-    let sanitized_input = input.replace("\r\n", "\n");
-    let (fresh, available) = sanitized_input.split_once("\n\n").ok_or("Invalid input, could not split on a blank line!".to_string())?;
+    let [fresh, available] = sections(input)[..] else { return Err("Invalid input, expected two sections separated by a blank line!".to_string()) };
 
-    let mut fresh_ingredients = Vec::new();
+    let fresh_ingredients = fresh.parse_lines(|p| {
+        let range_start = p.usize()?;
+        p.literal("-")?;
+        let range_end = p.usize()?;
 
-    let mut fresh_parser = Parser::new(fresh);
-    while !fresh_parser.is_exhausted() {
-        let range_start = fresh_parser.usize()?;
-        fresh_parser.literal("-")?;
-        let range_end = fresh_parser.usize()?;
-
-        fresh_ingredients.push(range_start..=range_end);
-    }
-
-    let available_ingredients = available.lines().map(|l| parse_usize(l)).collect::<Result<Vec<_>, _>>()?;
+        Ok(range_start..=range_end)
+    })?;
+    let available_ingredients = available.parse_lines(|p| p.usize())?;
 
     Ok(InventoryManagement { fresh_ingredients, available_ingredients })
 }
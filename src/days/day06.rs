@@ -1,7 +1,7 @@
 use std::str::FromStr;
 use crate::days::Day;
-use crate::util::number::parse_usize;
-use crate::util::parser::Parser;
+use crate::util::number::from_digits;
+use crate::util::parser::{Parser, Whitespace};
 
 pub const DAY6: Day = Day {
     puzzle1,
@@ -73,39 +73,41 @@ fn parse_input(input: &str) -> Result<Vec<MathProblem>, String> {
 }
 
 fn parse_input_p2(input: &str) -> Result<Vec<MathProblem>, String> {
-    // Numbers are written top-to-bottom, right to left. Whitespace matters.
-    let lines = input.lines().collect::<Vec<_>>();
-    let numbers = &lines[0..lines.len()-1];
-    let operators = lines[lines.len()-1];
+    // Numbers are written top-to-bottom, right to left. Whitespace matters, so all lines are consumed
+    // in lockstep, one column at a time.
+    let mut parsers = input.lines().map(|l| Parser::new(l).with_whitespace(Whitespace::None)).collect::<Vec<_>>();
+    let (operators, numbers) = parsers.split_last_mut().ok_or("Empty input")?;
 
     let mut result = vec![];
-    let mut column_idx = 0;
 
-    loop {
-        if column_idx >= operators.len() { break; }
-
-        let operator = operators[column_idx..column_idx+1].parse::<Operator>()?;
+    while !operators.is_exhausted() {
+        let operator = operators.one_of(vec!["+", "*"])?.parse::<Operator>()?;
         let mut values = vec![];
 
-        while let Some(number) = get_number_at(numbers, column_idx) {
+        while let Some(number) = parse_column(numbers)? {
             values.push(number);
-            column_idx += 1;
+            operators.optional(|p| p.space())?;
         }
 
         result.push(MathProblem { operator, values });
-        column_idx += 1; // Skip over the empty column found
     }
 
     Ok(result)
 }
 
-fn get_number_at(lines: &[&str], idx: usize) -> Option<usize> {
-    let digits = lines.iter().filter_map(|l| match l.chars().nth(idx) {
-        Some(c) if c.is_digit(10) => Some(parse_usize(&c.to_string()).unwrap()),
-        _ => None
-    }).collect::<Vec<_>>();
+/// Reads the digits in the next column of all lines, top to bottom. Yields `None` for an empty column.
+fn parse_column(lines: &mut [Parser]) -> Result<Option<usize>, String> {
+    let mut digits = vec![];
+
+    for line in lines {
+        match line.optional(|p| p.digit())? {
+            Some(digit) => digits.push(digit),
+            None if line.is_exhausted() => {},
+            None => line.space()?
+        }
+    }
 
-    digits.into_iter().reduce(|l, r| (l * 10) + r)
+    Ok((!digits.is_empty()).then(|| from_digits(digits, 10)))
 }
 
 impl FromStr for Operator {
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 use crate::days::Day;
-use crate::util::geometry::{p, Grid};
+use crate::util::geometry::{p, Grid, GridOptions, Point};
+use crate::util::number::BigInt;
 
 pub const DAY7: Day = Day {
     puzzle1,
@@ -29,7 +30,10 @@ enum Tile {
     Empty
 }
 
-type Manifold = Grid<Tile>;
+struct Manifold {
+    grid: Grid<Tile>,
+    start: Point,
+}
 
 impl Manifold {
     fn get_beam_split_count(&self) -> usize {
@@ -39,17 +43,17 @@ impl Manifold {
 
         // Since the beams move down the same time, we just keep track of the X coordinates of the
         // different beams.
-        let start = *self.entries().iter().find_map(|(p, t)| if Tile::Start.eq(t) { Some(p) } else { None }).unwrap();
+        let start = self.start;
         let mut split_count = 0;
 
         let mut beams = vec![start.x];
         let mut y = start.y + 1;
 
-        while y < self.bounds.height as isize {
+        while y < self.grid.bounds.height as isize {
             let mut new_beams = vec![];
 
             for beam in &beams {
-                match self.get(&p((*beam, y))) {
+                match self.grid.get(&p((*beam, y))) {
                     Some(Tile::Splitter) => {
                         split_count += 1;
 
@@ -70,33 +74,32 @@ impl Manifold {
         split_count
     }
 
-    fn count_split_timelines(&self) -> usize {
+    fn count_split_timelines(&self) -> BigInt {
         // Beam starts – going downwards – at Tile::Start.
         // This time, the particle splits the timeline when hitting a splitter.
         // How many timelines do we create in total?
 
-        let start = *self.entries().iter().find_map(|(p, t)| if Tile::Start.eq(t) { Some(p) } else { None }).unwrap();
-        let mut timelines = 1;
+        let start = self.start;
+        let mut timelines = BigInt::one();
 
-        let mut beams: HashMap<isize, usize> = HashMap::new(); // Map of x-index to the amount of 'particles' travelling there in different timelines.
-        beams.insert(start.x, 1);
+        // Map of x-index to the amount of 'particles' travelling there in different timelines.
+        // The amounts grow exponentially with the number of splitters, so they can get big.
+        let mut beams: HashMap<isize, BigInt> = HashMap::new();
+        beams.insert(start.x, BigInt::one());
         let mut y = start.y + 1;
 
-        while y < self.bounds.height as isize {
-            let mut new_beams = HashMap::new();
+        while y < self.grid.bounds.height as isize {
+            let mut new_beams: HashMap<isize, BigInt> = HashMap::new();
 
             for (beam, amount) in &beams {
-                match self.get(&p((*beam, y))) {
+                match self.grid.get(&p((*beam, y))) {
                     Some(Tile::Splitter) => {
                         timelines += amount;
 
-                        let left = beam - 1;
-                        let right = beam + 1;
-
-                        new_beams.insert(left, new_beams.get(&left).unwrap_or(&0) + amount);
-                        new_beams.insert(right, new_beams.get(&right).unwrap_or(&0) + amount);
+                        *new_beams.entry(beam - 1).or_default() += amount;
+                        *new_beams.entry(beam + 1).or_default() += amount;
                     },
-                    _ => { new_beams.insert(*beam, new_beams.get(beam).unwrap_or(&0) + amount); }
+                    _ => { *new_beams.entry(*beam).or_default() += amount; }
                 }
             }
 
@@ -108,7 +111,12 @@ impl Manifold {
     }
 }
 
-fn parse_input(input: &str) -> Result<Manifold, String> { input.parse() }
+fn parse_input(input: &str) -> Result<Manifold, String> {
+    let options = GridOptions { markers: vec!['S'], ..GridOptions::default() };
+    let parsed = Grid::parse_cells(input, &options, |cell, _| cell.parse())?;
+
+    Ok(Manifold { start: parsed.marker('S')?, grid: parsed.grid })
+}
 
 impl FromStr for Tile {
     type Err = String;
@@ -165,7 +173,8 @@ mod tests {
 
         let manifold = res.unwrap();
 
-        assert_eq!(manifold.get(&p((7, 0))), Some(Tile::Start));
+        assert_eq!(manifold.grid.get(&p((7, 0))), Some(Tile::Start));
+        assert_eq!(manifold.start, p((7, 0)));
     }
 
     #[test]
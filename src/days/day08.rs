@@ -1,4 +1,5 @@
 use crate::days::Day;
+use crate::util::collection::pairs;
 use crate::util::geometry::Point3D;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
@@ -101,17 +102,7 @@ struct JumperBoxPair {
 
 impl JumperBoxPair {
     fn create_pairs(points: &Vec<Point3D>) -> BinaryHeap<Self> {
-        let mut jumper_pairs: BinaryHeap<JumperBoxPair> = BinaryHeap::new();
-        for i in 0..points.len() {
-            for j in (i + 1)..points.len() {
-                jumper_pairs.push(JumperBoxPair {
-                    p1: points[i],
-                    p2: points[j],
-                });
-            }
-        }
-
-        jumper_pairs
+        pairs(points).map(|(p1, p2)| JumperBoxPair { p1, p2 }).collect()
     }
 
     fn len(&self) -> f64 {
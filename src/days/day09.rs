@@ -1,4 +1,5 @@
 use crate::days::Day;
+use crate::util::collection::pairs;
 use crate::util::geometry::{Point};
 
 pub const DAY9: Day = Day { puzzle1, puzzle2 };
@@ -26,18 +27,16 @@ fn find_largest_rectangle(points: &Vec<Point>) -> Option<Rectangle> {
     // Find the two points with the largest rectangle area between them.
     let mut result: Option<Rectangle> = None;
 
-    for i in 0..points.len() {
-        for j in (i + 1)..points.len() {
-            let rect = Rectangle {
-                corners: [points[i], points[j]],
-            };
-            if let Some(ref current) = result {
-                if current.area() < rect.area() {
-                    result = Some(rect)
-                }
-            } else {
+    for (p1, p2) in pairs(points) {
+        let rect = Rectangle {
+            corners: [p1, p2],
+        };
+        if let Some(ref current) = result {
+            if current.area() < rect.area() {
                 result = Some(rect)
             }
+        } else {
+            result = Some(rect)
         }
     }
 
@@ -57,17 +56,7 @@ fn find_largest_rectangle_in_path(path: &Vec<Point>) -> Option<Rectangle> {
         lines.push([p1,p2]);
     }
 
-    let mut rects = vec![];
-
-    for i in 0..path.len() {
-        for j in (i + 1)..path.len() {
-            let rect = Rectangle {
-                corners: [path[i], path[j]],
-            };
-
-            rects.push(rect);
-        }
-    }
+    let mut rects = pairs(path).map(|(p1, p2)| Rectangle { corners: [p1, p2] }).collect::<Vec<_>>();
 
     rects.sort_by_key(|r| r.area());
     rects.reverse();
@@ -1,9 +1,8 @@
 use crate::days::Day;
-use crate::util::collection::CollectionExtension;
-use crate::util::parser::Parser;
+use crate::util::collection::{subsets, CollectionExtension};
+use crate::util::layout::{layout, Flags, List};
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::str::FromStr;
 
 pub const DAY10: Day = Day { puzzle1, puzzle2 };
 
@@ -57,7 +56,8 @@ impl Machine {
             button_presses: vec![],
         });
 
-        let mut seen_states: HashSet<usize> = HashSet::new();
+        // Keyed on the leds themselves, so there's no limit on the amount of leds
+        let mut seen_states: HashSet<Vec<bool>> = HashSet::new();
 
         while let Some(state) = heap.pop() {
             if state.is_end_state(self) {
@@ -72,7 +72,7 @@ impl Machine {
                 .filter(|b| !state.button_presses.contains(b))
             {
                 let next_state = state.press_button(button);
-                if seen_states.insert(next_state.get_led_value()) {
+                if seen_states.insert(next_state.leds.clone()) {
                     heap.push(next_state);
                 }
             }
@@ -159,25 +159,6 @@ fn solve_joltage(
     min
 }
 
-// 0 => []
-// 1 -> 1
-// 2 -> 1, 1|2, 2
-// 3 => 1, 1|3, 1|2, 1|2|3, 2, 2|3, 3
-
-fn build_button_combinations(button_count: usize) -> Vec<Vec<usize>> {
-    if button_count == 0 {
-        return vec![vec![]];
-    }
-
-    let button_idx = button_count - 1;
-    let mut result = build_button_combinations(button_count - 1);
-
-    // For each entry in result (from the previous amount of buttons), we add a copy that adds this button. (And also leave the original)
-    result.push_all(&result.iter().map(|r| r.append_item(&button_idx)).collect());
-
-    result
-}
-
 fn compute_joltage(
     num_wires: usize,
     buttons: &Vec<ButtonDefinition>,
@@ -207,7 +188,8 @@ fn build_button_maps(num_wires: usize, buttons: &Vec<ButtonDefinition>) -> Butto
     let mut joltage_map = HashMap::new();
     let mut pattern_map: HashMap<Vec<bool>, Vec<Vec<usize>>> = HashMap::new();
 
-    for combination in build_button_combinations(buttons.len()) {
+    let button_indices = (0..buttons.len()).collect::<Vec<_>>();
+    for combination in subsets(&button_indices) {
         let joltage = compute_joltage(num_wires, buttons, &combination);
         let pattern = joltage.map(|v| v % 2 == 1);
         let presses = combination.len();
@@ -253,18 +235,6 @@ impl MachineLedState {
         }
     }
 
-    fn get_led_value(&self) -> usize {
-        // Map leds to bits, to get a unique value of the current state.
-        let mut result = 0;
-
-        for i in 0..self.leds.len() {
-            result <<= 1;
-            result |= self.leds[i] as usize;
-        }
-
-        result
-    }
-
     fn press_button(&self, button: &ButtonDefinition) -> Self {
         let mut leds = self.leds.clone();
         let mut button_presses = self.button_presses.clone();
@@ -285,57 +255,8 @@ fn parse_input(input: &str) -> Result<Vec<Machine>, String> {
     input.lines().map(|l| l.parse()).collect()
 }
 
-impl FromStr for Machine {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parser = Parser::new(s);
-
-        let mut leds = vec![];
-        let mut buttons = vec![];
-        let mut joulages = vec![];
-
-        parser.literal("[")?;
-        loop {
-            match parser.one_of(vec![".", "#", "]"])? {
-                "." => leds.push(false),
-                "#" => leds.push(true),
-                _ => break,
-            }
-        }
-
-        loop {
-            if parser.one_of(vec!["(", "{"])? == "{" {
-                break;
-            }
-
-            let mut wires = vec![];
-            loop {
-                wires.push(parser.usize()?);
-
-                if parser.one_of(vec![",", ")"])? == ")" {
-                    break;
-                }
-            }
-
-            buttons.push(ButtonDefinition { wires });
-        }
-
-        loop {
-            joulages.push(parser.usize()?);
-
-            if parser.one_of(vec![",", "}"])? == "}" {
-                break;
-            }
-        }
-
-        Ok(Machine {
-            leds,
-            buttons,
-            joltages: joulages,
-        })
-    }
-}
+layout!(ButtonDefinition { "(", wires as List<usize, ','>, ")" });
+layout!(Machine { "[", leds as Flags<'#', '.'>, "] ", buttons as List<ButtonDefinition, ' '>, " {", joltages as List<usize, ','>, "}" });
 
 // Ensure a state with fewer presses can come out of a BinaryHeap first
 impl Ord for MachineLedState {
@@ -344,7 +265,7 @@ impl Ord for MachineLedState {
             .button_presses
             .len()
             .cmp(&self.button_presses.len())
-            .then_with(|| self.get_led_value().cmp(&other.get_led_value()))
+            .then_with(|| self.leds.cmp(&other.leds))
     }
 }
 
@@ -357,9 +278,11 @@ impl PartialOrd for MachineLedState {
 #[cfg(test)]
 mod tests {
     use crate::days::day10::{
-        ButtonDefinition, Machine, MachineLedState, build_button_combinations, build_button_maps,
-        parse_input, solve_joltage,
+        ButtonDefinition, Machine, MachineLedState, build_button_maps, parse_input, solve_joltage,
     };
+    use crate::util::collection::subsets;
+    use crate::util::ilp::{IntegerProgram, Relation};
+    use crate::util::linalg::{Gf2, Matrix};
 
     const EXAMPLE_INPUT: &str = "\
         [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n\
@@ -393,39 +316,10 @@ mod tests {
     }
 
     #[test]
-    fn test_machine_state_get_led_value() {
-        assert_eq!(
-            MachineLedState {
-                leds: vec![false, true, false, true],
-                button_presses: vec![]
-            }
-            .get_led_value(),
-            5
-        );
-        assert_eq!(
-            MachineLedState {
-                leds: vec![true, false, false, false],
-                button_presses: vec![]
-            }
-            .get_led_value(),
-            8
-        );
-        assert_eq!(
-            MachineLedState {
-                leds: vec![true, true, true, true],
-                button_presses: vec![]
-            }
-            .get_led_value(),
-            15
-        );
-        assert_eq!(
-            MachineLedState {
-                leds: vec![true, false, false, false, false, false],
-                button_presses: vec![]
-            }
-            .get_led_value(),
-            32
-        );
+    fn test_machine_round_trip() {
+        for line in EXAMPLE_INPUT.lines() {
+            assert_eq!(line.parse::<Machine>().map(|m| m.to_string()), Ok(line.to_string()));
+        }
     }
 
     #[test]
@@ -467,6 +361,43 @@ mod tests {
                 .map(|p| p.len()),
             Some(2)
         );
+
+        // More leds than fit in a usize
+        let mut leds = vec![false; 70];
+        (leds[0], leds[69]) = (true, true);
+        let machine = Machine {
+            leds,
+            buttons: vec![
+                ButtonDefinition { wires: vec![0] },
+                ButtonDefinition { wires: vec![69] },
+                ButtonDefinition { wires: vec![0, 68] },
+                ButtonDefinition { wires: vec![68, 69] },
+            ],
+            joltages: vec![0; 70],
+        };
+        assert_eq!(machine.compute_least_button_presses_to_led_state().map(|p| p.len()), Some(2));
+    }
+
+    /// Part 1 as a linear system over GF(2): which buttons (each pressed at most once) toggle exactly the target leds.
+    fn least_button_presses_gf2(machine: &Machine) -> Option<usize> {
+        let matrix = Matrix::from_fn(machine.leds.len(), machine.buttons.len(), |led, button| {
+            Gf2(machine.buttons[button].wires.contains(&led))
+        });
+        let target = machine.leds.iter().map(|l| Gf2(*l)).collect::<Vec<_>>();
+
+        matrix.solve(&target)?.enumerate().map(|s| s.iter().filter(|v| v.0).count()).min()
+    }
+
+    #[test]
+    fn test_machine_least_button_presses_cross_check() {
+        let machines = parse_input(EXAMPLE_INPUT).unwrap();
+
+        for machine in &machines {
+            assert_eq!(
+                least_button_presses_gf2(machine),
+                machine.compute_least_button_presses_to_led_state().map(|p| p.len())
+            );
+        }
     }
 
     #[test]
@@ -478,16 +409,40 @@ mod tests {
         assert_eq!(machines[2].compute_joltage_button_presses(), Some(11));
     }
 
+    /// Part 2 as an integer program: minimize the total presses, with each wire reaching its joltage exactly.
+    fn joltage_button_presses_ilp(machine: &Machine) -> Option<usize> {
+        // A button can't be pressed more often than the lowest joltage of its wires
+        let bounds = machine.buttons.iter()
+            .map(|b| 0..=b.wires.iter().map(|w| machine.joltages[*w] as i64).min().unwrap_or(0))
+            .collect();
+        let mut program = IntegerProgram::new(vec![1; machine.buttons.len()], bounds);
+
+        for (wire, joltage) in machine.joltages.iter().enumerate() {
+            let coefficients = machine.buttons.iter().map(|b| b.wires.contains(&wire) as i64).collect();
+            program = program.with_constraint(coefficients, Relation::Equal, *joltage as i64);
+        }
+
+        program.solve().unwrap().map(|s| s.value as usize)
+    }
+
+    #[test]
+    fn test_machine_joltage_button_presses_cross_check() {
+        let machines = parse_input(EXAMPLE_INPUT).unwrap();
+
+        for machine in &machines {
+            assert_eq!(joltage_button_presses_ilp(machine), machine.compute_joltage_button_presses());
+        }
+    }
+
     #[test]
-    fn test_build_button_combinations() {
-        // assert_eq!(build_button_combinations(0), Vec<Vec<usize>>::new());
-        assert_eq!(build_button_combinations(1), vec![vec![], vec![0]]);
+    fn test_button_combinations() {
+        assert_eq!(subsets(&[0]).collect::<Vec<_>>(), vec![vec![], vec![0]]);
         assert_eq!(
-            build_button_combinations(2),
+            subsets(&[0, 1]).collect::<Vec<_>>(),
             vec![vec![], vec![0], vec![1], vec![0, 1],]
         );
         assert_eq!(
-            build_button_combinations(3),
+            subsets(&[0, 1, 2]).collect::<Vec<_>>(),
             vec![
                 vec![],
                 vec![0],
@@ -557,5 +512,6 @@ mod tests {
             ),
             Some(8 + 4 + 18 + 2 + 16 + 10 + 16 + 6 + 4)
         );
+        assert_eq!(joltage_button_presses_ilp(&machine), Some(8 + 4 + 18 + 2 + 16 + 10 + 16 + 6 + 4));
     }
 }
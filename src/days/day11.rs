@@ -1,5 +1,6 @@
 use crate::days::Day;
 use crate::util::collection::CollectionExtension;
+use crate::util::number::BigInt;
 use crate::util::parser::Parser;
 use std::collections::{HashMap, HashSet};
 
@@ -20,12 +21,12 @@ fn puzzle2(input: &String) -> Result<String, String> {
     Ok(format!("{}", result))
 }
 
-fn count_data_flows(map: &HashMap<String, Vec<String>>) -> usize {
+fn count_data_flows(map: &HashMap<String, Vec<String>>) -> BigInt {
     // Get the number of different paths from 'you' to 'out'
     traverse_to_out(&"you".to_string(), HashSet::new(), &vec![], map, &mut HashMap::new())
 }
 
-fn count_svr_flows(map: &HashMap<String, Vec<String>>) -> usize {
+fn count_svr_flows(map: &HashMap<String, Vec<String>>) -> BigInt {
     // Get the number of different paths from 'svr' to 'out', visiting 'fft' and 'dac'
     traverse_to_out(&"svr".to_string(), HashSet::new(), &vec!["fft".to_string(), "dac".to_string()], map, &mut HashMap::new())
 }
@@ -35,30 +36,30 @@ fn traverse_to_out(
     path: HashSet<String>,
     must_visit: &Vec<String>,
     map: &HashMap<String, Vec<String>>,
-    cache: &mut HashMap<String, usize>
-) -> usize {
+    cache: &mut HashMap<String, BigInt>
+) -> BigInt {
     let cache_key = must_visit.iter().cloned().filter(|v| path.contains(v)).collect::<Vec<_>>().append_item(current).join("->");
 
-    if let Some(&cached) = cache.get(&cache_key) {
-        cached
+    if let Some(cached) = cache.get(&cache_key) {
+        cached.clone()
     } else if path.contains(current) {
-        0
+        BigInt::zero()
     } else if current.eq("out") {
         if must_visit.iter().all(|v| path.contains(v)) {
-            1
+            BigInt::one()
         } else {
-            0
+            BigInt::zero()
         }
     } else if let Some(outs) = map.get(current) {
-        let mut result = 0;
+        let mut result = BigInt::zero();
         for out in outs {
             result += traverse_to_out(out, path.append_item(current), must_visit, map, cache);
         }
 
-        cache.insert(cache_key, result);
+        cache.insert(cache_key, result.clone());
         result
     } else {
-        0
+        BigInt::zero()
     }
 }
 
@@ -67,13 +68,11 @@ fn parse_input(input: &str) -> Result<HashMap<String, Vec<String>>, String> {
 
     for line in input.lines() {
         let mut parser = Parser::new(line);
-        let source = parser.str(3)?;
+        let source = parser.identifier()?;
         parser.literal(":")?;
 
-        let mut outs = vec![];
-        while !parser.is_exhausted() {
-            outs.push(parser.str(3)?);
-        }
+        let outs = parser.many(|p| p.word())?;
+        parser.ensure_exhausted()?;
 
         result.insert(source, outs);
     }
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 use crate::days::Day;
 use crate::util::collection::CollectionExtension;
 use crate::util::geometry::{Bounds, Grid, Point};
-use crate::util::parser::Parser;
+use crate::util::input::{sections, Section};
 
 pub const DAY12: Day = Day {
     puzzle1,
@@ -209,8 +209,7 @@ fn translate_shape<CX, CY>(shape: &Shape, cx: CX, cy: CY) -> Shape
 }
 
 fn parse_input(input: &str) -> Result<Puzzle, String> {
-    let lines = input.lines().collect::<Vec<_>>();
-    let sections = lines.split(|l| l.is_empty()).map(|l| l.iter().cloned().collect::<Vec<_>>()).collect::<Vec<_>>();
+    let sections = sections(input);
 
     let Some((areas_input, presents_input)) = sections.split_last() else { return Err("Could not successfully split input".to_string()) };
     let presents = parse_presents(presents_input)?;
@@ -219,33 +218,24 @@ fn parse_input(input: &str) -> Result<Puzzle, String> {
     Ok(Puzzle { presents, areas })
 }
 
-fn parse_presents(inputs: &[Vec<&str>]) -> Result<[Shape; 6], String> {
+fn parse_presents(inputs: &[Section]) -> Result<[Shape; 6], String> {
     if inputs.len() != 6 { return Err(format!("Invalid amount of presents: {}", inputs.len())) }
 
     let mut presents: [Shape; 6] = Default::default();
 
     for i in 0..6 {
-        presents[i] = parse_shape(&inputs[i])?;
+        presents[i] = parse_shape(&inputs[i].lines()).map_err(|e| inputs[i].error(e))?;
     }
 
     Ok(presents)
 }
 
-fn parse_areas(input: &Vec<&str>) -> Result<Vec<Area>, String> {
-    input.iter().map(|line| {
-        let mut parser = Parser::new(line);
-        let width = parser.usize()?;
-        parser.literal("x")?;
-        let height = parser.usize()?;
-        parser.literal(":")?;
-
-        let mut presents: [usize; 6] = Default::default();
-        for i in 0..6 {
-            presents[i] = parser.usize()?;
-        }
+fn parse_areas(input: &Section) -> Result<Vec<Area>, String> {
+    input.parse_lines(|p| {
+        let (width, height, a, b, c, d, e, f) = p.scan("{}x{}: {} {} {} {} {} {}")?;
 
-        Ok(Area { width, height, presents })
-    }).collect()
+        Ok(Area { width, height, presents: [a, b, c, d, e, f] })
+    })
 }
 
 fn parse_shape(input: &Vec<&str>) -> Result<Shape, String> {
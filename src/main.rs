@@ -8,36 +8,69 @@ use std::time::Instant;
 use days::{get_day, Day};
 use util::input::{read_input};
 use crate::util::number::parse_usize;
+use crate::util::cache::{cache_path, code_fingerprint, fingerprint, AnswerCache, CacheEntry};
+use crate::util::config::{self, Config, OutputFormat};
+use crate::util::submit::{ledger_path, submit, Ledger, SubmitSettings};
+use serde_json::json;
 
 fn print_usage()
 {
     eprintln!("
-Usage: cargo run <command> [<command_arg>, ...]
+Usage: cargo run [<option>, ...] <command> [<command_arg>, ...]
 
 Commands:
     day <day number> - run the puzzles for the given day.
     add <day number> - add base files and wiring for a new day.
-    race             - race through implemented days, keeping track of time.
+    race [--cached]  - race through implemented days, keeping track of time.
+                       With --cached, answers computed before for the same input and code are reused.
+                       Every run of day or race (also without --cached) stores its answers in the cache.
+    submit <day number> <part> - compute the answer for the given part and submit it.
+                                 (set AOC_SESSION or session_file to provide your session cookie)
+    config show      - print the effective settings.
+
+Options (override settings from aoc.toml):
+    --config <path>, --year <year>, --input-dir <path>, --trailing-whitespace <keep|trim>, --tab-width <width>,
+    --session-file <path>, --submit-url <url>, --timeout <seconds>, --format <text|json>, --template <path>
 ");
 }
 
 fn main() {
-    let a: Vec<String> = args().collect();
+    let (config, a) = match Config::from_args(&args().collect::<Vec<_>>()) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("{}", err);
+            return;
+        }
+    };
+    config::init(config);
 
-    match a[1].as_str() {
-        "race" if a.len() == 2 => {
+    match a.get(1).map(|c| c.as_str()).unwrap_or_default() {
+        "race" if a.len() == 2 || (a.len() == 3 && a[2] == "--cached") => {
+            let use_cache = a.len() == 3;
             let mut day = 1;
             let start = Instant::now();
 
-            while run_day(day) {
+            while run_day(day, use_cache) {
                 day += 1;
             }
 
-            println!("Finished AoC race: {}ms", Instant::now().duration_since(start).as_millis());
+            let millis = Instant::now().duration_since(start).as_millis();
+            match config::current().format {
+                OutputFormat::Text => println!("Finished AoC race: {}ms", millis),
+                OutputFormat::Json => println!("{}", json!({ "race": { "millis": millis } })),
+            }
         }
         "day" if a.len() == 3 => {
             let Some(day) = parse_usize(&a[2]).ok() else { panic!("Invalid day number {}", &a[2]) };
-            run_day(day);
+            run_day(day, false);
+        }
+        "submit" if a.len() == 4 => {
+            let Some(day) = parse_usize(&a[2]).ok() else { panic!("Invalid day number {}", &a[2]) };
+            let Some(part) = parse_usize(&a[3]).ok() else { panic!("Invalid part number {}", &a[3]) };
+            submit_answer(day, part);
+        }
+        "config" if a.len() == 3 && a[2] == "show" => {
+            println!("{}", config::current());
         }
         "add" if a.len() == 3 => {
             let Some(day) = parse_usize(&a[2]).ok() else { panic!("Invalid day number {}", &a[2]) };
@@ -49,31 +82,48 @@ fn main() {
     }
 }
 
-fn run_day(day_num: usize) -> bool
+fn run_day(day_num: usize, use_cache: bool) -> bool
 {
     let result: Result<(String, Day), String> = get_day(day_num).and_then(|day| read_input(day_num).and_then(|input| Ok((input, day))));
     match result {
         Ok((input, day)) => {
-            let p1_start = Instant::now();
-            match (day.puzzle1)(&input) {
-                Ok(res) => {
-                    println!("Day {} part 1 result: {} (took {}ms)", day_num, res, Instant::now().duration_since(p1_start).as_millis());
-                },
-                Err(err) => {
-                    eprintln!("Day {} part 1 failed: {} (took {}ms)", day_num, err, Instant::now().duration_since(p1_start).as_millis());
+            let input_fingerprint = fingerprint(&input);
+            let ledger = Ledger::load(ledger_path()).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                Ledger::default()
+            });
+            // It's just a cache, run without one if it cannot be read (but don't overwrite it either).
+            let mut cache = AnswerCache::load(cache_path()).inspect_err(|e| eprintln!("{}", e)).ok();
+
+            for (part, puzzle) in [(1, day.puzzle1), (2, day.puzzle2)] {
+                if use_cache && let Some(entry) = cache.as_ref().and_then(|c| c.get(day_num, part, &input_fingerprint, code_fingerprint())) {
+                    print_result(day_num, part, &entry.answer, entry.millis, true, ledger.correct_answer(day_num, part, &input_fingerprint));
+                    continue;
                 }
-            }
 
-            let p2_start = Instant::now();
-            match (day.puzzle2)(&input) {
-                Ok(res) => {
-                    println!("Day {} part 2 result: {} (took {}ms)", day_num, res, Instant::now().duration_since(p2_start).as_millis());
-                },
-                Err(err) => {
-                    eprintln!("Day {} part 2 failed: {} (took {}ms)", day_num, err, Instant::now().duration_since(p1_start).as_millis());
+                let start = Instant::now();
+                match puzzle(&input) {
+                    Ok(res) => {
+                        let millis = Instant::now().duration_since(start).as_millis();
+                        print_result(day_num, part, &res, millis, false, ledger.correct_answer(day_num, part, &input_fingerprint));
+                        if let Some(cache) = &mut cache {
+                            cache.store(CacheEntry { day: day_num, part, input: input_fingerprint.clone(), code: code_fingerprint().to_string(), millis, answer: res });
+                        }
+                    },
+                    Err(err) => {
+                        let millis = Instant::now().duration_since(start).as_millis();
+                        match config::current().format {
+                            OutputFormat::Text => eprintln!("Day {} part {} failed: {} (took {}ms)", day_num, part, err, millis),
+                            OutputFormat::Json => println!("{}", json!({ "day": day_num, "part": part, "error": err, "millis": millis })),
+                        }
+                    }
                 }
             }
 
+            if let Some(cache) = cache && let Err(err) = cache.save() {
+                eprintln!("{}", err);
+            }
+
             true
         }
         Err(err) => {
@@ -83,6 +133,71 @@ fn run_day(day_num: usize) -> bool
     }
 }
 
+/// Prints the result for a single part, comparing it with the answer accepted for the same input (if any).
+fn print_result(day_num: usize, part: usize, answer: &str, millis: u128, cached: bool, accepted: Option<&str>)
+{
+    match config::current().format {
+        OutputFormat::Text => {
+            let verified = match accepted {
+                Some(correct) if correct == answer => " [verified]".to_string(),
+                Some(correct) => format!(" [MISMATCH, accepted answer was {}]", correct),
+                None => "".to_string()
+            };
+            println!("Day {} part {} result: {} ({}took {}ms){}", day_num, part, answer, if cached { "cached, " } else { "" }, millis, verified);
+        }
+        OutputFormat::Json => {
+            println!("{}", json!({
+                "day": day_num,
+                "part": part,
+                "answer": answer,
+                "millis": millis,
+                "cached": cached,
+                "verified": accepted.map(|correct| correct == answer),
+            }));
+        }
+    }
+}
+
+/// Computes the answer for a single part, returning it together with the fingerprint of the input used.
+fn solve_part(day_num: usize, part: usize) -> Result<(String, String), String>
+{
+    let day = get_day(day_num)?;
+    let input = read_input(day_num)?;
+
+    let answer = match part {
+        1 => (day.puzzle1)(&input),
+        2 => (day.puzzle2)(&input),
+        _ => Err(format!("Invalid part {}, expected 1 or 2", part))
+    }?;
+
+    Ok((fingerprint(&input), answer))
+}
+
+fn submit_answer(day_num: usize, part: usize)
+{
+    let (input_fingerprint, answer) = match solve_part(day_num, part) {
+        Ok(solved) => solved,
+        Err(err) => {
+            eprintln!("Day {} part {} failed: {}", day_num, part, err);
+            return;
+        }
+    };
+
+    println!("Submitting day {} part {} answer: {}", day_num, part, answer);
+    let settings = match SubmitSettings::from_config() {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("{}", err);
+            return;
+        }
+    };
+
+    match submit(&settings, day_num, part, &input_fingerprint, &answer) {
+        Ok(response) => println!("{}: {}", response.verdict, response.message),
+        Err(err) => eprintln!("{}", err)
+    }
+}
+
 fn add_day(day: usize)
 {
     // This is going to be fun. Write code to modify the running code! Woohoo!
@@ -3,4 +3,10 @@ pub mod number;
 pub mod geometry;
 pub mod create_day;
 pub mod collection;
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+pub mod layout;
+pub mod submit;
+pub mod cache;
+pub mod config;
+pub mod linalg;
+pub mod ilp;
\ No newline at end of file
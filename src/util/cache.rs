@@ -0,0 +1,209 @@
+use std::env::current_exe;
+use std::fmt;
+use std::fs::{metadata, read_to_string, write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+use crate::util::input::input_dir;
+use crate::util::number::{parse_u128, parse_usize};
+
+/// Stable content hash (64-bit FNV-1a), used to recognize inputs across runs and machines.
+pub fn fingerprint(content: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    format!("{:016x}", hash)
+}
+
+/// Identifies the code that computed an answer. Any rebuild of the binary yields a new fingerprint,
+/// which is coarse, but guarantees we never serve an answer from outdated code.
+pub fn code_fingerprint() -> &'static str {
+    static CODE: OnceLock<String> = OnceLock::new();
+
+    CODE.get_or_init(|| {
+        let binary = current_exe().and_then(metadata).ok();
+        let modified = binary.as_ref()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let size = binary.map(|m| m.len()).unwrap_or(0);
+
+        fingerprint(&format!("{}:{}", size, modified))
+    })
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct CacheEntry {
+    pub day: usize,
+    pub part: usize,
+    pub input: String,
+    pub code: String,
+    pub millis: u128,
+    pub answer: String,
+}
+
+impl fmt::Display for CacheEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}\t{}\t{}\t{}\t{}", self.day, self.part, self.input, self.code, self.millis, escape(&self.answer))
+    }
+}
+
+impl FromStr for CacheEntry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.splitn(6, '\t').collect::<Vec<_>>();
+        if parts.len() != 6 {
+            return Err(format!("Invalid answer cache line: '{}'", s));
+        }
+
+        Ok(CacheEntry {
+            day: parse_usize(parts[0])?,
+            part: parse_usize(parts[1])?,
+            input: parts[2].to_string(),
+            code: parts[3].to_string(),
+            millis: parse_u128(parts[4])?,
+            answer: unescape(parts[5])?,
+        })
+    }
+}
+
+/// Answers are stored on a single tab separated line, so tabs, newlines and backslashes get escaped.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape(value: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('\\') => result.push('\\'),
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            other => return Err(format!("Invalid escape in answer cache: '\\{}'", other.map(String::from).unwrap_or_default())),
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn cache_path() -> PathBuf {
+    input_dir().join("answers.cache")
+}
+
+/// Computed answers (and how long they took), keyed by day, part and the input they were computed for.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct AnswerCache {
+    path: PathBuf,
+    pub entries: Vec<CacheEntry>,
+}
+
+impl AnswerCache {
+    pub fn new(path: PathBuf) -> Self {
+        AnswerCache { path, entries: vec![] }
+    }
+
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(AnswerCache::new(path));
+        }
+
+        let content = read_to_string(&path).map_err(|e| format!("Could not read answer cache {:?}: {}", path, e))?;
+        let entries = content.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.parse())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(AnswerCache { path, entries })
+    }
+
+    /// Finds the answer computed for this exact day, part, input and code.
+    pub fn get(&self, day: usize, part: usize, input: &str, code: &str) -> Option<&CacheEntry> {
+        self.entries.iter().find(|e| e.day == day && e.part == part && e.input == input && e.code == code)
+    }
+
+    /// Stores an answer, replacing whatever was computed for the same input before.
+    pub fn store(&mut self, entry: CacheEntry) {
+        self.entries.retain(|e| !(e.day == entry.day && e.part == entry.part && e.input == entry.input));
+        self.entries.push(entry);
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let content = self.entries.iter().map(|e| format!("{}\n", e)).collect::<String>();
+        write(&self.path, content).map_err(|e| format!("Could not write answer cache {:?}: {}", self.path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use crate::util::cache::{fingerprint, AnswerCache, CacheEntry};
+
+    fn entry(day: usize, part: usize, input: &str, code: &str, answer: &str) -> CacheEntry {
+        CacheEntry { day, part, input: input.to_string(), code: code.to_string(), millis: 12, answer: answer.to_string() }
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(""), "cbf29ce484222325");
+        assert_eq!(fingerprint("a"), "af63dc4c8601ec8c");
+        assert_ne!(fingerprint("L68\nL30\n"), fingerprint("L68\nL31\n"));
+    }
+
+    #[test]
+    fn test_cache_entry_round_trip() {
+        let entry = entry(4, 1, "af63dc4c8601ec8c", "cbf29ce484222325", "1234");
+
+        assert_eq!(entry.to_string(), "4\t1\taf63dc4c8601ec8c\tcbf29ce484222325\t12\t1234");
+        assert_eq!(entry.to_string().parse(), Ok(entry.clone()));
+
+        let multi_line = CacheEntry { answer: "#..#\n#\t\\n".to_string(), ..entry.clone() };
+        assert_eq!(multi_line.to_string(), "4\t1\taf63dc4c8601ec8c\tcbf29ce484222325\t12\t#..#\\n#\\t\\\\n");
+        assert_eq!(multi_line.to_string().parse(), Ok(multi_line));
+
+        assert!("4\t2\tinput\tcode\t12\tbad\\x".parse::<CacheEntry>().is_err());
+    }
+
+    #[test]
+    fn test_cache_save_and_load() {
+        let path = std::env::temp_dir().join(format!("aoc-cache-{}.cache", std::process::id()));
+        let mut cache = AnswerCache::new(path.clone());
+        cache.store(entry(10, 1, "input", "code", "#..#\n#..#\n"));
+        cache.store(entry(10, 2, "input", "code", "42"));
+        assert_eq!(cache.save(), Ok(()));
+
+        assert_eq!(AnswerCache::load(path.clone()), Ok(cache));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_cache_get_and_store() {
+        let mut cache = AnswerCache::new(PathBuf::new());
+        cache.store(entry(1, 1, "input-a", "code-1", "10"));
+        cache.store(entry(1, 1, "input-b", "code-1", "20"));
+
+        assert_eq!(cache.get(1, 1, "input-a", "code-1").map(|e| e.answer.as_str()), Some("10"));
+        assert_eq!(cache.get(1, 1, "input-b", "code-1").map(|e| e.answer.as_str()), Some("20"));
+        assert_eq!(cache.get(1, 2, "input-a", "code-1"), None);
+        assert_eq!(cache.get(1, 1, "input-a", "code-2"), None);
+
+        // Recomputing with new code replaces the answer for that input only.
+        cache.store(entry(1, 1, "input-a", "code-2", "11"));
+        assert_eq!(cache.entries.len(), 2);
+        assert_eq!(cache.get(1, 1, "input-a", "code-2").map(|e| e.answer.as_str()), Some("11"));
+    }
+}
@@ -92,4 +92,174 @@ impl<T> VecToString for Vec<T> where T : ToString {
     fn to_string(&self) -> Vec<String> {
         self.iter().map(|s| s.to_string()).collect()
     }
-}
\ No newline at end of file
+}
+
+/// All unordered pairs `(items[i], items[j])` with `i < j`.
+pub fn pairs<T: Clone>(items: &[T]) -> impl Iterator<Item = (T, T)> + '_ {
+    items.iter().enumerate()
+        .flat_map(move |(i, a)| items[i + 1..].iter().map(move |b| (a.clone(), b.clone())))
+}
+
+/// All subsets of `items`, in the order of their bitmask: `[]`, `[a]`, `[b]`, `[a, b]`, `[c]`, ...
+/// Panics for 64 or more items, as there would be too many subsets to ever go through.
+pub fn subsets<T: Clone>(items: &[T]) -> impl Iterator<Item = Vec<T>> + '_ {
+    assert!(items.len() < 64, "Too many items to enumerate all subsets ({})", items.len());
+
+    (0..1u64 << items.len()).map(|mask| {
+        items.iter().enumerate().filter(|(i, _)| mask >> i & 1 == 1).map(|(_, v)| v.clone()).collect()
+    })
+}
+
+/// All combinations of `k` items, keeping the order of `items`, in lexicographic order of their positions.
+pub fn combinations<T: Clone>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations { items, indices: (k <= items.len()).then(|| (0..k).collect()) }
+}
+
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    /// Positions of the next combination, `None` when done.
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for Combinations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let indices = self.indices.as_mut()?;
+        let result = indices.iter().map(|i| self.items[*i].clone()).collect();
+
+        // Advance the rightmost position that can still move, resetting the ones after it
+        let (n, k) = (self.items.len(), indices.len());
+        match (0..k).rev().find(|i| indices[*i] < n - k + i) {
+            Some(i) => {
+                indices[i] += 1;
+                for j in i + 1..k {
+                    indices[j] = indices[j - 1] + 1;
+                }
+            },
+            None => self.indices = None,
+        }
+
+        Some(result)
+    }
+}
+
+/// All orderings of `items`, in lexicographic order of their positions.
+pub fn permutations<T: Clone>(items: &[T]) -> Permutations<'_, T> {
+    Permutations { items, indices: Some((0..items.len()).collect()) }
+}
+
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    /// Positions of the next permutation, `None` when done.
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for Permutations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let indices = self.indices.as_mut()?;
+        let result = indices.iter().map(|i| self.items[*i].clone()).collect();
+
+        // Next lexicographic permutation: swap the last ascent with the smallest larger value after it,
+        // then reverse the (descending) tail.
+        match (1..indices.len()).rev().find(|i| indices[i - 1] < indices[*i]) {
+            Some(i) => {
+                let j = (i..indices.len()).rev().find(|j| indices[*j] > indices[i - 1]).expect("An ascent has a larger value after it");
+                indices.swap(i - 1, j);
+                indices[i..].reverse();
+            },
+            None => self.indices = None,
+        }
+
+        Some(result)
+    }
+}
+
+/// All ways to pick one item from each list, varying the last list fastest.
+pub fn cartesian_product<T: Clone>(lists: &[Vec<T>]) -> CartesianProduct<'_, T> {
+    let indices = if lists.iter().any(|l| l.is_empty()) { None } else { Some(vec![0; lists.len()]) };
+
+    CartesianProduct { lists, indices }
+}
+
+pub struct CartesianProduct<'a, T> {
+    lists: &'a [Vec<T>],
+    /// Positions of the next product, `None` when done.
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for CartesianProduct<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let indices = self.indices.as_mut()?;
+        let result = indices.iter().zip(self.lists).map(|(i, l)| l[*i].clone()).collect();
+
+        // Odometer-style increment
+        let lists = self.lists;
+        let carried = indices.iter_mut().zip(lists).rev().all(|(i, l)| {
+            *i = (*i + 1) % l.len();
+            *i == 0
+        });
+        if carried { self.indices = None; }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::collection::{cartesian_product, combinations, pairs, permutations, subsets};
+
+    #[test]
+    fn test_pairs() {
+        assert_eq!(pairs(&[1, 2, 3]).collect::<Vec<_>>(), vec![(1, 2), (1, 3), (2, 3)]);
+        assert_eq!(pairs(&[1]).count(), 0);
+    }
+
+    #[test]
+    fn test_subsets() {
+        assert_eq!(subsets(&['a', 'b', 'c']).collect::<Vec<_>>(), vec![
+            vec![], vec!['a'], vec!['b'], vec!['a', 'b'], vec!['c'], vec!['a', 'c'], vec!['b', 'c'], vec!['a', 'b', 'c']
+        ]);
+        assert_eq!(subsets::<u8>(&[]).collect::<Vec<_>>(), vec![Vec::<u8>::new()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many items to enumerate all subsets (64)")]
+    fn test_subsets_too_many_items() {
+        let _ = subsets(&[0u8; 64]);
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(combinations(&[1, 2, 3, 4], 2).collect::<Vec<_>>(), vec![
+            vec![1, 2], vec![1, 3], vec![1, 4], vec![2, 3], vec![2, 4], vec![3, 4]
+        ]);
+        assert_eq!(combinations(&[1, 2, 3], 0).collect::<Vec<_>>(), vec![Vec::<i32>::new()]);
+        assert_eq!(combinations(&[1, 2, 3], 3).count(), 1);
+        assert_eq!(combinations(&[1, 2, 3], 4).count(), 0);
+        assert_eq!(combinations(&(0..10).collect::<Vec<_>>(), 4).count(), 210);
+    }
+
+    #[test]
+    fn test_permutations() {
+        assert_eq!(permutations(&[1, 2, 3]).collect::<Vec<_>>(), vec![
+            vec![1, 2, 3], vec![1, 3, 2], vec![2, 1, 3], vec![2, 3, 1], vec![3, 1, 2], vec![3, 2, 1]
+        ]);
+        assert_eq!(permutations(&[1, 1]).count(), 2);
+        assert_eq!(permutations::<u8>(&[]).count(), 1);
+        assert_eq!(permutations(&[0; 6]).count(), 720);
+    }
+
+    #[test]
+    fn test_cartesian_product() {
+        assert_eq!(cartesian_product(&[vec![1, 2], vec![3], vec![4, 5]]).collect::<Vec<_>>(), vec![
+            vec![1, 3, 4], vec![1, 3, 5], vec![2, 3, 4], vec![2, 3, 5]
+        ]);
+        assert_eq!(cartesian_product(&[vec![1, 2], vec![]]).count(), 0);
+        assert_eq!(cartesian_product::<u8>(&[]).count(), 1);
+    }
+}
@@ -0,0 +1,265 @@
+use std::env;
+use std::fmt;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use crate::util::input::TrailingWhitespace;
+use crate::util::number::{parse_u64, parse_usize};
+
+const CONFIG_FILE: &str = "aoc.toml";
+const SETTINGS: [&str; 9] = ["year", "input_dir", "trailing_whitespace", "tab_width", "session_file", "submit_url", "timeout", "format", "template"];
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown output format: {} (expected text or json)", s))
+        }
+    }
+}
+
+/// Runner settings. Read from `aoc.toml`, with environment variables and CLI flags (`--<setting> <value>`,
+/// using dashes instead of underscores) taking precedence, in that order.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Config {
+    pub year: usize,
+    pub input_dir: PathBuf,
+    pub trailing_whitespace: TrailingWhitespace,
+    pub tab_width: Option<usize>,
+    /// File holding the session cookie used to submit answers; `AOC_SESSION` wins if it is set.
+    pub session_file: Option<PathBuf>,
+    /// Endpoint to post answers to; `{year}` and `{day}` are filled in.
+    pub submit_url: String,
+    /// Timeout for requests to the submit endpoint, in seconds.
+    pub timeout: u64,
+    pub format: OutputFormat,
+    pub template: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            year: 2025,
+            input_dir: repository_path("resources"),
+            trailing_whitespace: TrailingWhitespace::Keep,
+            tab_width: None,
+            session_file: None,
+            submit_url: "https://adventofcode.com/{year}/day/{day}/answer".to_string(),
+            timeout: 30,
+            format: OutputFormat::Text,
+            template: repository_path("resources/day.rs.hbs"),
+        }
+    }
+}
+
+/// Paths are relative to the working directory, falling back to this repository so the binary can be run from anywhere.
+fn repository_path(path: &str) -> PathBuf {
+    let local = PathBuf::from(path);
+    if local.exists() {
+        local
+    } else {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+    }
+}
+
+impl Config {
+    /// Builds the effective configuration from the config file, environment and command line flags.
+    /// Returns the configuration together with the arguments that are not settings.
+    pub fn from_args(args: &[String]) -> Result<(Config, Vec<String>), String> {
+        let mut config_file = None;
+        let mut flags = vec![];
+        let mut remaining = vec![];
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.strip_prefix("--") {
+                Some("config") => config_file = Some(PathBuf::from(iter.next().ok_or("Missing value for --config")?)),
+                Some(key) if Config::is_setting(key) => {
+                    let value = iter.next().ok_or(format!("Missing value for {}", arg))?;
+                    flags.push((key.replace('-', "_"), value.clone()));
+                }
+                _ => remaining.push(arg.clone()),
+            }
+        }
+
+        let mut config = Config::default();
+
+        match config_file {
+            Some(path) => config.apply_file(&path)?,
+            None => {
+                let path = repository_path(CONFIG_FILE);
+                if path.exists() { config.apply_file(&path)?; }
+            }
+        }
+
+        if let Ok(dir) = env::var("AOC_INPUT_DIR") { config.set("input_dir", &dir)?; }
+        if let Ok(url) = env::var("AOC_SUBMIT_URL") { config.set("submit_url", &url)?; }
+
+        for (key, value) in flags {
+            config.set(&key, &value)?;
+        }
+
+        Ok((config, remaining))
+    }
+
+    fn is_setting(key: &str) -> bool {
+        SETTINGS.contains(&key.replace('-', "_").as_str())
+    }
+
+    pub fn apply_file(&mut self, path: &PathBuf) -> Result<(), String> {
+        let content = read_to_string(path).map_err(|e| format!("Could not read config file {:?}: {}", path, e))?;
+        self.apply_toml(&content).map_err(|e| format!("Invalid config file {:?}: {}", path, e))
+    }
+
+    pub fn apply_toml(&mut self, content: &str) -> Result<(), String> {
+        let table = content.parse::<toml::Table>().map_err(|e| e.message().to_string())?;
+
+        for (key, value) in table {
+            let value = match value {
+                toml::Value::String(s) => s,
+                toml::Value::Integer(i) => i.to_string(),
+                other => return Err(format!("Unsupported value for {}: {}", key, other)),
+            };
+            self.set(&key, &value)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "year" => self.year = parse_usize(value)?,
+            "input_dir" => self.input_dir = PathBuf::from(value),
+            "trailing_whitespace" => self.trailing_whitespace = value.parse()?,
+            "tab_width" if value.is_empty() || value == "none" => self.tab_width = None,
+            "tab_width" => self.tab_width = Some(parse_usize(value)?),
+            "session_file" if value.is_empty() => self.session_file = None,
+            "session_file" => self.session_file = Some(PathBuf::from(value)),
+            "submit_url" => self.submit_url = value.to_string(),
+            "timeout" => self.timeout = parse_u64(value)?,
+            "format" => self.format = value.parse()?,
+            "template" => self.template = PathBuf::from(value),
+            _ => return Err(format!("Unknown setting '{}'", key)),
+        }
+
+        Ok(())
+    }
+
+    /// The session cookie to submit answers with, from `AOC_SESSION` or the configured session file.
+    pub fn session(&self) -> Result<Option<String>, String> {
+        if let Ok(session) = env::var("AOC_SESSION") {
+            return Ok(Some(session));
+        }
+
+        match &self.session_file {
+            Some(path) => read_to_string(path)
+                .map(|s| Some(s.trim().to_string()))
+                .map_err(|e| format!("Could not read session file {:?}: {}", path, e)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "year = {}", self.year)?;
+        writeln!(f, "input_dir = {:?}", self.input_dir.display().to_string())?;
+        writeln!(f, "trailing_whitespace = \"{}\"", self.trailing_whitespace)?;
+        match self.tab_width {
+            Some(width) => writeln!(f, "tab_width = {}", width)?,
+            None => writeln!(f, "# tab_width is not set")?,
+        }
+        match &self.session_file {
+            Some(path) => writeln!(f, "session_file = {:?}", path.display().to_string())?,
+            None => writeln!(f, "# session_file is not set")?,
+        }
+        writeln!(f, "submit_url = {:?}", self.submit_url)?;
+        writeln!(f, "timeout = {}", self.timeout)?;
+        writeln!(f, "format = \"{}\"", self.format)?;
+        write!(f, "template = {:?}", self.template.display().to_string())
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Makes the configuration available to the rest of the runner. Only the first call has any effect.
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+pub fn current() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use crate::util::config::{Config, OutputFormat};
+    use crate::util::input::TrailingWhitespace;
+
+    #[test]
+    fn test_apply_toml() {
+        let mut config = Config::default();
+        let res = config.apply_toml("\
+            year = 2024\n\
+            input_dir = \"inputs\"\n\
+            trailing_whitespace = \"trim\"\n\
+            tab_width = 4\n\
+            format = \"json\"\n\
+        ");
+
+        assert!(res.is_ok(), "{:?}", res);
+        assert_eq!(config.year, 2024);
+        assert_eq!(config.input_dir, PathBuf::from("inputs"));
+        assert_eq!(config.trailing_whitespace, TrailingWhitespace::Trim);
+        assert_eq!(config.tab_width, Some(4));
+        assert_eq!(config.format, OutputFormat::Json);
+
+        assert!(Config::default().apply_toml("unknown = 1").is_err());
+        assert!(Config::default().apply_toml("format = \"xml\"").is_err());
+        assert!(Config::default().apply_toml("year = [2024]").is_err());
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = ["aoc", "--year", "2023", "race", "--cached", "--timeout", "5", "--input-dir", "elsewhere"].map(|s| s.to_string());
+        let (config, remaining) = Config::from_args(&args).unwrap();
+
+        assert_eq!(remaining, vec!["aoc", "race", "--cached"]);
+        assert_eq!(config.year, 2023);
+        assert_eq!(config.timeout, 5);
+        assert_eq!(config.input_dir, PathBuf::from("elsewhere"));
+
+        assert!(Config::from_args(&["aoc".to_string(), "--year".to_string()]).is_err());
+        assert!(Config::from_args(&["aoc", "--config", "does-not-exist.toml"].map(|s| s.to_string())).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let mut round_trip = Config::default();
+        let config = Config { year: 2023, tab_width: Some(8), format: OutputFormat::Json, ..Config::default() };
+
+        assert!(round_trip.apply_toml(&config.to_string()).is_ok());
+        assert_eq!(round_trip, config);
+    }
+}
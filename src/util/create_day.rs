@@ -5,6 +5,8 @@ use std::env::{current_dir};
 use regex::{Captures, RegexBuilder};
 use handlebars::{Handlebars};
 use serde_json::{json};
+use crate::util::config;
+use crate::util::input::input_path;
 
 pub fn create_day(day: usize) -> Result<(), String> {
     match get_day(day) {
@@ -15,8 +17,7 @@ pub fn create_day(day: usize) -> Result<(), String> {
             };
             let source_file_name = format!("src/days/day{:02}.rs", day);
             let source_path = main_dir.join(Path::new(&source_file_name));
-            let template_file_name = format!("resources/day{:02}.txt", day);
-            let input_path = main_dir.join(Path::new(&template_file_name));
+            let input_path = main_dir.join(input_path(day));
             let module_file_name = "src/days.rs".to_string();
             let module_path = main_dir.join(Path::new(&module_file_name));
 
@@ -27,7 +28,7 @@ pub fn create_day(day: usize) -> Result<(), String> {
                 return Err(format!("Input file for day {} already exists.", day));
             }
 
-            let template = match read_to_string(main_dir.join("resources/day.rs.hbs")) {
+            let template = match read_to_string(main_dir.join(&config::current().template)) {
                 Ok(v) => { v }
                 Err(e) => { return Err(format!("Could not read day template: {}", e)); }
             };
@@ -8,7 +8,8 @@ use std::hash::Hash;
 use std::ops::{Add, RangeInclusive, Sub};
 use std::str::FromStr;
 use num_traits::{abs, Zero};
-use crate::util::number;
+use crate::util::layout::layout;
+use crate::util::number::{BigInt, Rational};
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
 pub struct Point {
@@ -80,21 +81,7 @@ impl TryFrom<(usize, usize)> for Point {
     }
 }
 
-impl FromStr for Point {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts_result: Result<Vec<isize>, String> = s.split(",").map(|p| number::parse_isize(p.trim())).collect();
-        let parts = match parts_result {
-            Ok(v) => v,
-            Err(e) => return Err(e)
-        };
-        match parts.len() {
-            2 => Ok((parts[0], parts[1]).into()),
-            _ => Err(format!("Invalid str format for Point '{}', expected 'x,y'", s))
-        }
-    }
-}
+layout!(Point { x, ",", y } without Display);
 
 impl Ord for Point {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -182,6 +169,7 @@ mod point_tests {
         assert_eq!("3,5".parse(), Ok(Point { x: 3, y: 5 }));
         assert_eq!("3,-5".parse(), Ok(Point { x: 3, y: -5 }));
         assert_eq!("422,-2345".parse(), Ok(Point { x: 422, y: -2345 }));
+        assert!("3,5,2".parse::<Point>().is_err());
     }
 
     #[test]
@@ -269,18 +257,7 @@ impl From<Point> for Point3D {
     }
 }
 
-impl FromStr for Point3D {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let points = s.split(",").map(|p| number::parse_isize(p.trim())).collect::<Result<Vec<isize>, String>>()?;
-        if points.len() != 3 {
-            Err(format!("Expected three coordinates, but got {}", points.len()))
-        } else {
-            Ok(Point3D { x: points[0], y: points[1], z: points[2] })
-        }
-    }
-}
+layout!(Point3D { x, ",", y, ",", z } without Display);
 
 impl PartialOrd<Self> for Point3D {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -355,6 +332,7 @@ mod point3d_tests {
         assert_eq!("3,5,2".parse(), Ok(Point3D { x: 3, y: 5, z: 2 }));
         assert_eq!("3,-5,0".parse(), Ok(Point3D { x: 3, y: -5, z: 0 }));
         assert_eq!("422,-2345,-99".parse(), Ok(Point3D { x: 422, y: -2345, z: -99 }));
+        assert!("3,5".parse::<Point3D>().is_err());
     }
 
     #[test]
@@ -472,12 +450,46 @@ impl Line {
             Some((num_x/denominator, num_y/denominator))
         }
     }
+
+    /// Like `intersection`, but exact: the coordinates are fractions, so checking whether the lines cross
+    /// at an integer point is reliable, also for large coordinates. The products involved don't fit in any
+    /// primitive, so they're computed with big integers; yields `None` if a reduced coordinate doesn't fit
+    /// in an `i128`.
+    pub fn intersection_exact(&self, other: &Self) -> Option<(Rational<i128>, Rational<i128>)> {
+        let (x1, y1) = (BigInt::from(self.start.x), BigInt::from(self.start.y));
+        let (x2, y2) = (BigInt::from(self.end.x), BigInt::from(self.end.y));
+        let (x3, y3) = (BigInt::from(other.start.x), BigInt::from(other.start.y));
+        let (x4, y4) = (BigInt::from(other.end.x), BigInt::from(other.end.y));
+
+        let denominator = ((&x1 - &x2) * (&y3 - &y4)) - ((&y1 - &y2) * (&x3 - &x4));
+        if denominator.is_zero() {
+            return None;
+        }
+
+        let cross_a = (&x1 * &y2) - (&y1 * &x2);
+        let cross_b = (&x3 * &y4) - (&y3 * &x4);
+        let num_x = (&cross_a * (&x3 - &x4)) - ((&x1 - &x2) * &cross_b);
+        let num_y = (&cross_a * (&y3 - &y4)) - ((&y1 - &y2) * &cross_b);
+
+        Some((exact_fraction(num_x, &denominator)?, exact_fraction(num_y, &denominator)?))
+    }
+}
+
+/// `numer / denom` as a reduced fraction of `i128`s, if it fits.
+fn exact_fraction(numer: BigInt, denom: &BigInt) -> Option<Rational<i128>> {
+    let (mut a, mut b) = (numer.abs(), denom.abs());
+    while !b.is_zero() {
+        (a, b) = (b.clone(), &a % &b);
+    }
+
+    Some(Rational::new((&numer / &a).to_i128()?, (denom / &a).to_i128()?))
 }
 
 
 #[cfg(test)]
 mod line_tests {
     use crate::util::geometry::{Line, Point};
+    use crate::util::number::Rational;
 
     const fn point(x: isize, y: isize) -> Point {
         Point { x, y }
@@ -508,6 +520,33 @@ mod line_tests {
         let b = line(20,25,18,23);
         assert_eq!(a.intersection(&b), None);
     }
+
+    #[test]
+    fn test_intersection_exact() {
+        let a = line(19, 13, 17, 14);
+        let b = line(18, 19, 17, 18);
+        assert_eq!(a.intersection_exact(&b), Some((Rational::new(43, 3), Rational::new(46, 3))));
+
+        let a = line(18,19,17,18);
+        let b = line(12,31,11,29);
+        assert_eq!(a.intersection_exact(&b), Some((Rational::from(-6), Rational::from(-5))));
+        let b = line(20,25,18,23);
+        assert_eq!(a.intersection_exact(&b), None);
+
+        // Floats can't tell these apart from an integer point
+        let a = line(0, 0, 1_000_000_001, 1_000_000_000);
+        let b = line(0, 1, 1, 1);
+        let (x, y) = a.intersection_exact(&b).unwrap();
+        assert_eq!((x, y.to_integer()), (Rational::new(1_000_000_001, 1_000_000_000), Some(1)));
+
+        // The intermediate products of coordinates this large don't fit in an i128
+        let (px, py) = (123_456_789_012_345, 98_765_432_109_876);
+        let a = line(px - 30_000_000_000_000, py - 70_000_000_000_000, px + 60_000_000_000_000, py + 140_000_000_000_000);
+        let b = line(px + 50_000_000_000_000, py - 20_000_000_000_000, px - 100_000_000_000_000, py + 40_000_000_000_000);
+        assert_eq!(a.intersection_exact(&b), Some((Rational::from(px as i128), Rational::from(py as i128))));
+        let b = line(px + 1, py, px - 99_999_999_999_999, py + 40_000_000_000_000);
+        assert_eq!(a.intersection_exact(&b).map(|(x, _)| x.is_integer()), Some(false));
+    }
 }
 
 
@@ -805,22 +844,117 @@ impl<T> FromStr for Grid<T> where T: FromStr + Clone + Default {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parse_result: Result<Vec<Vec<T>>, String> = s.lines()
-            .filter(|l| !l.is_empty())
-            .map(|l| l.chars().map(|c|
-                String::from(c).parse::<T>().map_err(|_| format!("Could not parse '{}' to {}", c, std::any::type_name::<T>())))
-                .collect::<Result<Vec<T>, String>>())
-            .collect();
-
-        let cells = match parse_result {
-            Ok(lines) if lines.len() == 0 => {
-                return Ok(Grid::default());
+        Grid::parse_cells(s, &GridOptions::default(), |cell, _| {
+            cell.parse::<T>().map_err(|_| format!("Could not parse '{}' to {}", cell, std::any::type_name::<T>()))
+        }).map(|parsed| parsed.grid)
+    }
+}
+
+/// How a line of grid input is divided into cells.
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+pub enum CellLayout {
+    /// Every character is a cell.
+    #[default]
+    Chars,
+    /// Cells of a fixed amount of characters, e.g. right-aligned numbers. Surrounding spaces are trimmed.
+    Width(usize),
+    /// Cells separated by a character. A whitespace separator also allows runs of whitespace (`"10 23  4"`).
+    Separator(char),
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct GridOptions {
+    pub cells: CellLayout,
+    /// Pad rows that are shorter than the widest row with the default value, instead of rejecting the input.
+    pub pad_ragged: bool,
+    /// Characters whose positions should be collected, like the start position of a maze.
+    pub markers: Vec<char>,
+}
+
+/// A parsed grid, together with the positions of the requested markers.
+#[derive(Eq, PartialEq, Clone)]
+pub struct ParsedGrid<T> where T: Clone {
+    pub grid: Grid<T>,
+    pub markers: HashMap<char, Vec<Point>>,
+}
+
+impl<T> ParsedGrid<T> where T: Clone {
+    /// The position of a marker that should appear exactly once.
+    pub fn marker(&self, marker: char) -> Result<Point, String> {
+        match self.markers.get(&marker).map(|points| points.as_slice()) {
+            Some([point]) => Ok(*point),
+            Some(points) if points.len() > 1 => Err(format!("Marker '{}' found {} times, expected once", marker, points.len())),
+            _ => Err(format!("Marker '{}' not found", marker))
+        }
+    }
+}
+
+impl<T> Grid<T> where T: Clone {
+    /// Parses a grid with one cell per character, converting every character with `parse`.
+    pub fn parse_with<F>(input: &str, mut parse: F) -> Result<Self, String>
+        where F: FnMut(char, Point) -> Result<T, String> {
+        let parsed = Self::parse_rows(input, &GridOptions::default(), None, |cell, point| {
+            parse(cell.chars().next().unwrap_or_default(), point)
+        })?;
+
+        Ok(parsed.grid)
+    }
+
+    /// Parses a grid according to `options`, converting every cell with `parse`.
+    pub fn parse_cells<F>(input: &str, options: &GridOptions, parse: F) -> Result<ParsedGrid<T>, String>
+        where F: FnMut(&str, Point) -> Result<T, String>, T: Default {
+        Self::parse_rows(input, options, options.pad_ragged.then(T::default), parse)
+    }
+
+    fn parse_rows<F>(input: &str, options: &GridOptions, pad: Option<T>, mut parse: F) -> Result<ParsedGrid<T>, String>
+        where F: FnMut(&str, Point) -> Result<T, String> {
+        let mut cells = HashMap::new();
+        let mut markers: HashMap<char, Vec<Point>> = HashMap::new();
+        let mut widths = vec![];
+
+        for (y, line) in input.lines().filter(|l| !l.is_empty()).enumerate() {
+            let mut width = 0;
+
+            for (x, cell) in split_cells(line, options.cells).enumerate() {
+                let point = p((x as isize, y as isize));
+                let value = parse(cell, point).map_err(|e| format!("line {}, cell {}: {}", y + 1, x + 1, e))?;
+
+                let mut chars = cell.chars();
+                if let (Some(c), None) = (chars.next(), chars.next()) && options.markers.contains(&c) {
+                    markers.entry(c).or_default().push(point);
+                }
+
+                cells.insert(point, value);
+                width += 1;
             }
-            Ok(lines) => lines,
-            Err(e) => return Err(e)
-        };
 
-        Grid::try_from(cells)
+            widths.push(width);
+        }
+
+        let width = widths.iter().max().copied().unwrap_or(0);
+        for (y, row_width) in widths.iter().enumerate().filter(|(_, w)| **w < width) {
+            match &pad {
+                Some(default) => (*row_width..width).for_each(|x| { cells.insert(p((x as isize, y as isize)), default.clone()); }),
+                None => return Err(format!("Not all lines in input are the same width (line {} has {} cells, expected {})", y + 1, row_width, width)),
+            }
+        }
+
+        let bounds = Bounds { top: 0, left: 0, width, height: widths.len() };
+        Ok(ParsedGrid { grid: Grid { bounds, cells }, markers })
+    }
+}
+
+/// Splits a line of grid input into cells, each a (trimmed) slice of `line`.
+fn split_cells(line: &str, layout: CellLayout) -> Box<dyn Iterator<Item = &str> + '_> {
+    match layout {
+        CellLayout::Chars => Box::new(line.char_indices().map(move |(i, c)| &line[i..i + c.len_utf8()])),
+        CellLayout::Width(width) => {
+            let starts = line.char_indices().map(|(i, _)| i).step_by(width.max(1)).collect::<Vec<_>>();
+            let ends = starts.iter().skip(1).copied().chain([line.len()]).collect::<Vec<_>>();
+            Box::new(starts.into_iter().zip(ends).map(move |(start, end)| line[start..end].trim()))
+        }
+        CellLayout::Separator(c) if c.is_whitespace() => Box::new(line.split_whitespace()),
+        CellLayout::Separator(c) => Box::new(line.split(c).map(|cell| cell.trim())),
     }
 }
 
@@ -850,7 +984,7 @@ impl<T> TryFrom<Vec<Vec<T>>> for Grid<T> where T: Clone + Default {
 
 #[cfg(test)]
 mod grid_tests {
-    use crate::util::geometry::{Grid, Directions, Bounds};
+    use crate::util::geometry::{p, Grid, Directions, Bounds, CellLayout, GridOptions};
 
     const EXAMPLE_GRID_INPUT: &str = "\
         2199943210\n\
@@ -978,4 +1112,47 @@ mod grid_tests {
             (-2, 3).into(), (-1, 3).into(), (0, 3).into(), (1, 3).into(), (2, 3).into(),
         ]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_grid_parse_with() {
+        let grid = Grid::parse_with("#.\n.#\n", |c, point| match c {
+            '#' => Ok(point.x + point.y),
+            '.' => Ok(-1),
+            _ => Err(format!("Unknown cell '{}'", c))
+        });
+
+        assert_eq!(grid.map(|g| g.to_string()), Ok(" 0-1\n-1 2".to_string()));
+        assert_eq!(Grid::parse_with("#x", |c, _| if c == '#' { Ok(1) } else { Err("Unknown cell".to_string()) }).map(|_| ()),
+                   Err("line 1, cell 2: Unknown cell".to_string()));
+        assert!(Grid::parse_with("##\n#", |_, _| Ok(1)).is_err());
+    }
+
+    #[test]
+    fn test_grid_parse_cells() {
+        let options = GridOptions { cells: CellLayout::Separator(' '), pad_ragged: true, ..GridOptions::default() };
+        let parsed = Grid::parse_cells("10 23  4\n5\n", &options, |cell, _| cell.parse::<usize>().map_err(|e| e.to_string())).unwrap();
+
+        assert_eq!(parsed.grid.bounds, Bounds { top: 0, left: 0, width: 3, height: 2 });
+        assert_eq!(parsed.grid.get(&p((2, 0))), Some(4));
+        assert_eq!(parsed.grid.get(&p((2, 1))), Some(0));
+
+        let options = GridOptions { cells: CellLayout::Width(3), ..GridOptions::default() };
+        let parsed = Grid::parse_cells(" 10 23  4\n  1  2  3", &options, |cell, _| cell.parse::<usize>().map_err(|e| e.to_string())).unwrap();
+        assert_eq!(parsed.grid.get(&p((1, 0))), Some(23));
+        assert_eq!(parsed.grid.get(&p((2, 1))), Some(3));
+
+        let options = GridOptions { cells: CellLayout::Separator(','), ..GridOptions::default() };
+        assert!(Grid::parse_cells("1,2\n3", &options, |cell, _| Ok(cell.to_string())).is_err());
+    }
+
+    #[test]
+    fn test_grid_markers() {
+        let options = GridOptions { markers: vec!['S', 'E'], ..GridOptions::default() };
+        let parsed = Grid::parse_cells("S.#\n.#E\n", &options, |cell, _| Ok(cell == "#")).unwrap();
+
+        assert_eq!(parsed.marker('S'), Ok(p((0, 0))));
+        assert_eq!(parsed.marker('E'), Ok(p((2, 1))));
+        assert!(parsed.marker('#').is_err());
+        assert_eq!(parsed.grid.get(&p((1, 1))), Some(true));
+    }
+}
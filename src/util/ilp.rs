@@ -0,0 +1,344 @@
+// Allow dead_code since this is a util file copied across years. Later in the AoC we might use everything, or not.
+#![allow(dead_code)]
+
+use std::ops::RangeInclusive;
+use num_traits::{One, Zero};
+use crate::util::number::Rational;
+
+type Value = Rational<i128>;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Relation {
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+}
+
+/// `coefficients · x (relation) rhs`
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Constraint {
+    pub coefficients: Vec<i64>,
+    pub relation: Relation,
+    pub rhs: i64,
+}
+
+/// Outcome of solving the linear relaxation, where variables don't need to be integers.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum LpResult {
+    Optimal { value: Value, values: Vec<Value> },
+    Infeasible,
+    Unbounded,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct IntegerSolution {
+    pub value: i64,
+    pub values: Vec<i64>,
+}
+
+/// Minimizes `objective · x` over integer vectors `x` with every variable in its (inclusive) bounds and all
+/// constraints satisfied. Solved with branch and bound on top of an exact simplex, so there is no rounding
+/// to worry about.
+///
+/// ```ignore
+/// // Minimize x + y, with x + 2y = 7, both in 0..=10
+/// let program = IntegerProgram::new(vec![1, 1], vec![0..=10, 0..=10])
+///     .with_constraint(vec![1, 2], Relation::Equal, 7);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IntegerProgram {
+    objective: Vec<i64>,
+    bounds: Vec<RangeInclusive<i64>>,
+    constraints: Vec<Constraint>,
+}
+
+impl IntegerProgram {
+    pub fn new(objective: Vec<i64>, bounds: Vec<RangeInclusive<i64>>) -> IntegerProgram {
+        IntegerProgram { objective, bounds, constraints: vec![] }
+    }
+
+    pub fn with_constraint(mut self, coefficients: Vec<i64>, relation: Relation, rhs: i64) -> IntegerProgram {
+        self.constraints.push(Constraint { coefficients, relation, rhs });
+        self
+    }
+
+    /// The optimal integer solution, or `None` if there is none.
+    pub fn solve(&self) -> Result<Option<IntegerSolution>, String> {
+        self.validate()?;
+
+        let mut best = None;
+        self.branch(&mut vec![], &mut best)?;
+
+        Ok(best)
+    }
+
+    /// The optimum of the linear relaxation, ignoring integrality.
+    pub fn relaxation(&self) -> Result<LpResult, String> {
+        self.validate()?;
+
+        Ok(self.solve_relaxation(&[]))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let n = self.objective.len();
+        if self.bounds.len() != n {
+            return Err(format!("Expected bounds for {} variables, got {}", n, self.bounds.len()));
+        }
+        if let Some((i, c)) = self.constraints.iter().enumerate().find(|(_, c)| c.coefficients.len() != n) {
+            return Err(format!("Constraint {} has {} coefficients, expected {}", i, c.coefficients.len(), n));
+        }
+
+        Ok(())
+    }
+
+    /// Depth first branch and bound. `extra` holds the bounds tightened by the branches taken so far.
+    fn branch(&self, extra: &mut Vec<Constraint>, best: &mut Option<IntegerSolution>) -> Result<(), String> {
+        let (value, values) = match self.solve_relaxation(extra) {
+            LpResult::Optimal { value, values } => (value, values),
+            LpResult::Infeasible => return Ok(()),
+            LpResult::Unbounded => return Err("Integer program is unbounded".to_string()),
+        };
+
+        // With integer coefficients, an integer solution can't be better than the rounded up relaxation
+        if let Some(current) = best && value.ceil() >= current.value as i128 {
+            return Ok(());
+        }
+
+        let Some(variable) = values.iter().position(|v| !v.is_integer()) else {
+            let values = values.iter().map(|v| v.numer() as i64).collect();
+            *best = Some(IntegerSolution { value: value.numer() as i64, values });
+            return Ok(());
+        };
+
+        let floor = values[variable].floor() as i64;
+        for (relation, rhs) in [(Relation::LessOrEqual, floor), (Relation::GreaterOrEqual, floor + 1)] {
+            let mut coefficients = vec![0; self.objective.len()];
+            coefficients[variable] = 1;
+
+            extra.push(Constraint { coefficients, relation, rhs });
+            let result = self.branch(extra, best);
+            extra.pop();
+            result?;
+        }
+
+        Ok(())
+    }
+
+    fn solve_relaxation(&self, extra: &[Constraint]) -> LpResult {
+        // Shift every variable by its lower bound, so the simplex can assume x >= 0
+        let lower = self.bounds.iter().map(|b| *b.start() as i128).collect::<Vec<_>>();
+        let shift = |coefficients: &[i64]| coefficients.iter().zip(&lower).map(|(c, l)| *c as i128 * l).sum::<i128>();
+
+        let mut rows = vec![];
+        for constraint in self.constraints.iter().chain(extra) {
+            let coefficients = constraint.coefficients.iter().map(|c| *c as i128).collect();
+            rows.push((coefficients, constraint.relation, constraint.rhs as i128 - shift(&constraint.coefficients)));
+        }
+        for (i, bound) in self.bounds.iter().enumerate() {
+            let mut coefficients = vec![0; self.bounds.len()];
+            coefficients[i] = 1;
+            rows.push((coefficients, Relation::LessOrEqual, *bound.end() as i128 - lower[i]));
+        }
+
+        let objective = self.objective.iter().map(|c| Value::from(*c as i128)).collect::<Vec<_>>();
+        match simplex(&objective, &rows) {
+            LpResult::Optimal { value, values } => LpResult::Optimal {
+                value: value + Value::from(shift(&self.objective)),
+                values: values.iter().zip(&lower).map(|(v, l)| *v + Value::from(*l)).collect(),
+            },
+            other => other,
+        }
+    }
+}
+
+/// Two-phase simplex minimizing `objective · x` for `x >= 0`, using Bland's rule so it can't cycle.
+fn simplex(objective: &[Value], constraints: &[(Vec<i128>, Relation, i128)]) -> LpResult {
+    let n = objective.len();
+    let m = constraints.len();
+
+    // Columns: the variables, then a slack or surplus per inequality, then an artificial per row that needs one
+    let slack_count = constraints.iter().filter(|(_, r, _)| *r != Relation::Equal).count();
+    let mut tableau = Tableau { rows: vec![], rhs: vec![], basis: vec![] };
+    let mut artificials = vec![];
+    let mut slack = n;
+
+    for (coefficients, relation, rhs) in constraints {
+        // Keep the right hand side non-negative, so the starting basis is feasible
+        let sign = if *rhs < 0 { -1 } else { 1 };
+        let relation = match (relation, sign) {
+            (Relation::LessOrEqual, -1) => Relation::GreaterOrEqual,
+            (Relation::GreaterOrEqual, -1) => Relation::LessOrEqual,
+            (relation, _) => *relation,
+        };
+
+        let mut row = coefficients.iter().map(|c| Value::from(c * sign)).collect::<Vec<_>>();
+        row.resize(n + slack_count + m, Value::zero());
+
+        let basic = match relation {
+            Relation::LessOrEqual => {
+                row[slack] = Value::one();
+                slack += 1;
+                slack - 1
+            },
+            Relation::GreaterOrEqual | Relation::Equal => {
+                if relation == Relation::GreaterOrEqual {
+                    row[slack] = -Value::one();
+                    slack += 1;
+                }
+                let artificial = n + slack_count + artificials.len();
+                row[artificial] = Value::one();
+                artificials.push(artificial);
+                artificial
+            },
+        };
+
+        tableau.rows.push(row);
+        tableau.rhs.push(Value::from(rhs * sign));
+        tableau.basis.push(basic);
+    }
+
+    let columns = n + slack_count + artificials.len();
+    for row in tableau.rows.iter_mut() {
+        row.truncate(columns);
+    }
+
+    // Phase 1: minimize the sum of the artificials to find a feasible basis
+    let mut cost = vec![Value::zero(); columns];
+    artificials.iter().for_each(|a| cost[*a] = Value::one());
+    let allowed = vec![true; columns];
+
+    if !tableau.optimize(&cost, &allowed) || tableau.value(&cost) > Value::zero() {
+        return LpResult::Infeasible;
+    }
+
+    // Drive remaining (zero valued) artificials out of the basis where possible
+    for row in 0..tableau.rows.len() {
+        if artificials.contains(&tableau.basis[row]) &&
+            let Some(col) = (0..n + slack_count).find(|c| !tableau.rows[row][*c].is_zero()) {
+            tableau.pivot(row, col);
+        }
+    }
+
+    // Phase 2: the actual objective, never letting an artificial back in
+    let mut cost = objective.to_vec();
+    cost.resize(columns, Value::zero());
+    let allowed = (0..columns).map(|c| c < n + slack_count).collect::<Vec<_>>();
+
+    if !tableau.optimize(&cost, &allowed) {
+        return LpResult::Unbounded;
+    }
+
+    let mut values = vec![Value::zero(); n];
+    for (row, basic) in tableau.basis.iter().enumerate() {
+        if *basic < n { values[*basic] = tableau.rhs[row]; }
+    }
+
+    LpResult::Optimal { value: tableau.value(&cost), values }
+}
+
+struct Tableau {
+    rows: Vec<Vec<Value>>,
+    rhs: Vec<Value>,
+    basis: Vec<usize>,
+}
+
+impl Tableau {
+    fn value(&self, cost: &[Value]) -> Value {
+        self.basis.iter().zip(&self.rhs).fold(Value::zero(), |acc, (b, v)| acc + cost[*b] * *v)
+    }
+
+    /// Pivots until optimal (`true`) or until the objective turns out to be unbounded (`false`).
+    fn optimize(&mut self, cost: &[Value], allowed: &[bool]) -> bool {
+        loop {
+            // Bland's rule: the first column with a negative reduced cost enters
+            let entering = (0..cost.len()).find(|col| {
+                allowed[*col] && !self.basis.contains(col) && {
+                    let reduced = self.rows.iter().zip(&self.basis)
+                        .fold(cost[*col], |acc, (row, b)| acc - cost[*b] * row[*col]);
+                    reduced < Value::zero()
+                }
+            });
+            let Some(col) = entering else { return true };
+
+            // Ratio test, breaking ties on the lowest basic variable
+            let leaving = (0..self.rows.len())
+                .filter(|row| self.rows[*row][col] > Value::zero())
+                .min_by(|a, b| {
+                    (self.rhs[*a] / self.rows[*a][col]).cmp(&(self.rhs[*b] / self.rows[*b][col]))
+                        .then(self.basis[*a].cmp(&self.basis[*b]))
+                });
+            let Some(row) = leaving else { return false };
+
+            self.pivot(row, col);
+        }
+    }
+
+    fn pivot(&mut self, row: usize, col: usize) {
+        let pivot = self.rows[row][col];
+        self.rows[row].iter_mut().for_each(|v| *v /= pivot);
+        self.rhs[row] /= pivot;
+
+        for other in (0..self.rows.len()).filter(|r| *r != row) {
+            let factor = self.rows[other][col];
+            if factor.is_zero() { continue; }
+
+            for c in 0..self.rows[other].len() {
+                let delta = factor * self.rows[row][c];
+                self.rows[other][c] -= delta;
+            }
+            let delta = factor * self.rhs[row];
+            self.rhs[other] -= delta;
+        }
+
+        self.basis[row] = col;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::ilp::{IntegerProgram, IntegerSolution, LpResult, Relation};
+    use crate::util::number::Rational;
+
+    #[test]
+    fn test_relaxation() {
+        // Maximize x + y (minimize the negation) with x + 2y <= 4, 3x + y <= 6
+        let program = IntegerProgram::new(vec![-1, -1], vec![0..=10, 0..=10])
+            .with_constraint(vec![1, 2], Relation::LessOrEqual, 4)
+            .with_constraint(vec![3, 1], Relation::LessOrEqual, 6);
+
+        assert_eq!(program.relaxation(), Ok(LpResult::Optimal {
+            value: Rational::new(-14, 5),
+            values: vec![Rational::new(8, 5), Rational::new(6, 5)],
+        }));
+        assert_eq!(program.solve(), Ok(Some(IntegerSolution { value: -2, values: vec![1, 1] })));
+    }
+
+    #[test]
+    fn test_solve() {
+        // Minimize x + y with x + 2y = 7
+        let program = IntegerProgram::new(vec![1, 1], vec![0..=10, 0..=10])
+            .with_constraint(vec![1, 2], Relation::Equal, 7);
+        assert_eq!(program.solve(), Ok(Some(IntegerSolution { value: 4, values: vec![1, 3] })));
+
+        // Lower bounds and >= constraints
+        let program = IntegerProgram::new(vec![2, 3], vec![-5..=5, 1..=5])
+            .with_constraint(vec![1, 1], Relation::GreaterOrEqual, 0);
+        assert_eq!(program.solve(), Ok(Some(IntegerSolution { value: 1, values: vec![-1, 1] })));
+    }
+
+    #[test]
+    fn test_infeasible() {
+        // 2x = 3 has a rational, but no integer solution
+        let program = IntegerProgram::new(vec![1], vec![0..=10])
+            .with_constraint(vec![2], Relation::Equal, 3);
+        assert!(matches!(program.relaxation(), Ok(LpResult::Optimal { .. })));
+        assert_eq!(program.solve(), Ok(None));
+
+        let program = IntegerProgram::new(vec![1], vec![0..=10])
+            .with_constraint(vec![1], Relation::GreaterOrEqual, 11);
+        assert_eq!(program.relaxation(), Ok(LpResult::Infeasible));
+
+        assert!(IntegerProgram::new(vec![1, 1], vec![0..=1]).solve().is_err());
+        assert!(IntegerProgram::new(vec![1], vec![0..=1]).with_constraint(vec![1, 1], Relation::Equal, 1).solve().is_err());
+    }
+}
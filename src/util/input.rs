@@ -1,10 +1,307 @@
+use std::fmt;
 use std::fs::{read_to_string, exists};
+use std::path::PathBuf;
+use std::str::FromStr;
+use crate::days::get_day;
+use crate::util::config;
+use crate::util::parser::{Parser, Whitespace};
+
+const PLACEHOLDER_INPUT: &str = "TODO: Add Content Here";
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+pub enum TrailingWhitespace {
+    /// Leave trailing whitespace on lines alone (some puzzles use column-aligned input).
+    #[default]
+    Keep,
+    /// Strip trailing whitespace from every line.
+    Trim,
+}
+
+impl fmt::Display for TrailingWhitespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrailingWhitespace::Keep => write!(f, "keep"),
+            TrailingWhitespace::Trim => write!(f, "trim"),
+        }
+    }
+}
+
+impl FromStr for TrailingWhitespace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(TrailingWhitespace::Keep),
+            "trim" => Ok(TrailingWhitespace::Trim),
+            _ => Err(format!("Unknown trailing whitespace policy: {} (expected keep or trim)", s))
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct InputOptions {
+    pub trailing_whitespace: TrailingWhitespace,
+    /// When set, tabs are expanded to spaces up to the next multiple of this width.
+    pub tab_width: Option<usize>,
+    pub ensure_final_newline: bool,
+}
+
+impl Default for InputOptions {
+    fn default() -> Self {
+        InputOptions { trailing_whitespace: TrailingWhitespace::Keep, tab_width: None, ensure_final_newline: true }
+    }
+}
 
 pub fn read_input(day: usize) -> Result<String, String> {
-    let input_path = format!("resources/day{:02}.txt", day);
+    let config = config::current();
+    let options = InputOptions { trailing_whitespace: config.trailing_whitespace, tab_width: config.tab_width, ..InputOptions::default() };
+
+    read_input_with(day, &options)
+}
+
+/// Directory puzzle inputs live in, see `input_dir` in `aoc.toml`.
+pub fn input_dir() -> PathBuf {
+    config::current().input_dir.clone()
+}
+
+pub fn input_path(day: usize) -> PathBuf {
+    input_dir().join(format!("day{:02}.txt", day))
+}
+
+pub fn read_input_with(day: usize, options: &InputOptions) -> Result<String, String> {
+    let input_path = input_path(day);
+
+    let raw = match exists(&input_path) {
+        Ok(true) => read_to_string(&input_path).map_err(|e| format!("Could not read input for day {} from {:?}: {}", day, input_path, e)),
+        Ok(false) => Err(missing_input_message(day, &input_path)),
+        Err(e) => Err(format!("Could not check for input for day {} at {:?}: {}", day, input_path, e)),
+    }?;
+
+    let (input, warnings) = normalize(&raw, options);
+    for warning in warnings {
+        eprintln!("Warning: input for day {} {}", day, warning);
+    }
+
+    Ok(input)
+}
+
+fn missing_input_message(day: usize, input_path: &PathBuf) -> String {
+    let suggestion = match get_day(day) {
+        Ok(_) => format!("Save your puzzle input from https://adventofcode.com/{}/day/{} to that file", config::current().year, day),
+        Err(_) => format!("Run `cargo run add {}` to create the day and its input file", day),
+    };
+
+    format!("Input for day {} not found at {:?}. {}, or point input_dir (aoc.toml, AOC_INPUT_DIR or --input-dir) to the directory holding your inputs.", day, input_path, suggestion)
+}
+
+/// Normalizes raw puzzle input so days don't have to care about the platform it was saved on.
+/// Returns the normalized input, together with warnings about anything that looks off.
+pub fn normalize(raw: &str, options: &InputOptions) -> (String, Vec<String>) {
+    let mut warnings = vec![];
+
+    let without_bom = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+
+    if without_bom.trim().is_empty() {
+        warnings.push("is empty".to_string());
+    } else if without_bom.trim() == PLACEHOLDER_INPUT {
+        warnings.push("still contains the placeholder content, did you forget to paste your input?".to_string());
+    }
+    if without_bom.contains('\t') && options.tab_width.is_none() {
+        warnings.push("contains tab characters".to_string());
+    }
+    if !without_bom.is_ascii() {
+        warnings.push("contains non-ASCII characters".to_string());
+    }
+
+    let lines = without_bom.replace("\r\n", "\n").replace('\r', "\n");
+    let mut result = lines.split('\n')
+        .map(|line| {
+            let expanded = match options.tab_width {
+                Some(width) => expand_tabs(line, width),
+                None => line.to_string(),
+            };
+
+            match options.trailing_whitespace {
+                TrailingWhitespace::Keep => expanded,
+                TrailingWhitespace::Trim => expanded.trim_end().to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if options.ensure_final_newline && !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+
+    (result, warnings)
+}
+
+fn expand_tabs(line: &str, width: usize) -> String {
+    let mut result = String::new();
+    let mut column = 0;
+
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = width - (column % width);
+            result.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            result.push(c);
+            column += 1;
+        }
+    }
+
+    result
+}
+
+/// A block of input lines, separated from other sections by blank lines.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Section<'a> {
+    /// One-based position of the section in the input.
+    pub number: usize,
+    pub content: &'a str,
+}
+
+impl<'a> Section<'a> {
+    pub fn lines(&self) -> Vec<&'a str> {
+        self.content.lines().collect()
+    }
+
+    /// A parser for this section, which mentions the section in its errors.
+    pub fn parser(&self) -> Parser {
+        Parser::new(self.content).with_section(self.number)
+    }
+
+    /// Parses the whole section with `item`.
+    pub fn parse<T, F>(&self, item: F) -> Result<T, String>
+        where F: FnOnce(&mut Parser) -> Result<T, String> {
+        let mut parser = self.parser();
+        let result = item(&mut parser).and_then(|value| parser.ensure_exhausted().map(|_| value));
+
+        result.map_err(|e| self.error(e))
+    }
+
+    /// Parses every line of the section with `item`. Newlines are significant while parsing a line.
+    pub fn parse_lines<T, F>(&self, mut item: F) -> Result<Vec<T>, String>
+        where F: FnMut(&mut Parser) -> Result<T, String> {
+        self.parse(|parser| {
+            parser.set_whitespace(Whitespace::Spaces);
+
+            let mut result = vec![];
+            while !parser.is_exhausted() {
+                result.push(item(parser)?);
+                if !parser.is_exhausted() {
+                    parser.newline()?;
+                }
+            }
+
+            Ok(result)
+        })
+    }
+
+    /// Makes sure an error mentions this section, also when it did not come from the section's parser.
+    pub fn error(&self, message: String) -> String {
+        if message.starts_with("section ") {
+            message
+        } else {
+            format!("section {}: {}", self.number, message)
+        }
+    }
+}
+
+/// Splits the input into sections on (one or more) blank lines.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut result = vec![];
+    let mut start = None;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        match start {
+            Some(from) if line.trim().is_empty() => {
+                result.push(Section { number: result.len() + 1, content: &input[from..offset] });
+                start = None;
+            }
+            None if !line.trim().is_empty() => start = Some(offset),
+            _ => {}
+        }
+        offset += line.len();
+    }
+
+    if let Some(from) = start {
+        result.push(Section { number: result.len() + 1, content: &input[from..] });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::input::{missing_input_message, normalize, sections, InputOptions, TrailingWhitespace};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_normalize_line_endings() {
+        let (input, warnings) = normalize("\u{feff}1-2\r\n3-4\r\n\r\n5", &InputOptions::default());
+
+        assert_eq!(input, "1-2\n3-4\n\n5\n");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_trailing_whitespace() {
+        let keep = InputOptions::default();
+        let trim = InputOptions { trailing_whitespace: TrailingWhitespace::Trim, ..InputOptions::default() };
+
+        assert_eq!(normalize("12 \n 3  \n", &keep).0, "12 \n 3  \n");
+        assert_eq!(normalize("12 \n 3  \n", &trim).0, "12\n 3\n");
+    }
+
+    #[test]
+    fn test_normalize_tabs() {
+        let options = InputOptions { tab_width: Some(4), ..InputOptions::default() };
+
+        assert_eq!(normalize("a\tb\n\tc\n", &options).0, "a   b\n    c\n");
+        assert_eq!(normalize("a\tb\n", &InputOptions::default()).1, vec!["contains tab characters"]);
+    }
+
+    #[test]
+    fn test_normalize_warnings() {
+        assert_eq!(normalize("", &InputOptions::default()).1, vec!["is empty"]);
+        assert_eq!(normalize("TODO: Add Content Here", &InputOptions::default()).1.len(), 1);
+        assert_eq!(normalize("L10\nR5\n", &InputOptions::default()).1.len(), 0);
+    }
+
+    #[test]
+    fn test_missing_input_message() {
+        let path = PathBuf::from("resources/day01.txt");
+
+        assert!(missing_input_message(1, &path).contains("Save your puzzle input"));
+        assert!(missing_input_message(99, &path).contains("cargo run add 99"));
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "\n1-2\n3-4\n\n\n5\n6\n\n7";
+        let sections = sections(input);
+
+        assert_eq!(sections.iter().map(|s| s.number).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(sections[0].content, "1-2\n3-4\n");
+        assert_eq!(sections[1].lines(), vec!["5", "6"]);
+        assert_eq!(sections[2].content, "7");
+
+        let ranges = sections[0].parse_lines(|p| { let start = p.usize()?; p.literal("-")?; Ok((start, p.usize()?)) });
+        assert_eq!(ranges, Ok(vec![(1, 2), (3, 4)]));
+        assert_eq!(sections[1].parse(|p| p.many(|p| p.usize())), Ok(vec![5, 6]));
+    }
+
+    #[test]
+    fn test_section_errors() {
+        let sections = sections("1\n\n5\n6\n7 8\n");
+
+        let error = sections[1].parse_lines(|p| p.usize()).unwrap_err();
+        assert!(error.starts_with("section 2, line 3, column 3: Expected a newline"), "{}", error);
 
-    match exists(&input_path) {
-        Ok(_) => read_to_string(&input_path).map_err(|e| format!("{}", e)),
-        Err(_) => Err(format!("Input for day {} not found in resources directory!", day)),
+        let error = sections[0].parse(|_| Err::<(), _>("Invalid shape".to_string())).unwrap_err();
+        assert_eq!(error, "section 1: Invalid shape");
     }
 }
@@ -0,0 +1,240 @@
+use std::fmt;
+use std::marker::PhantomData;
+use crate::util::number::ParseInteger;
+use crate::util::parser::Parser;
+
+/// Describes the textual layout of a record once, generating `Layout`, `FromStr` and a `Display` that round-trips.
+///
+/// Items are separated by commas and are either literal text, a field (parsed with the `Layout` of its type) or
+/// a field with a `Codec` (`field as Codec`). Whitespace around literal text is optional when parsing:
+///
+/// ```ignore
+/// layout!(ButtonDefinition { "(", wires as List<usize, ','>, ")" });
+/// layout!(enum Direction { Left = "L", Right = "R" });
+/// ```
+///
+/// Types with a `Display` for another purpose can use `layout!(Type { ... } without Display)`.
+macro_rules! layout {
+    (enum $name:ident { $($variant:ident = $text:literal),+ $(,)? }) => {
+        impl $crate::util::layout::Layout for $name {
+            fn parse(parser: &mut $crate::util::parser::Parser) -> Result<Self, String> {
+                match parser.one_of(vec![$($text),+])? {
+                    $($text => Ok($name::$variant),)+
+                    other => Err(format!("Unknown {}: {}", stringify!($name), other))
+                }
+            }
+
+            fn write(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $($name::$variant => write!(f, "{}", $text),)+
+                }
+            }
+        }
+
+        layout!(@from_str $name);
+        layout!(@display $name);
+    };
+    ($name:ident { $($items:tt)* } without Display) => {
+        layout!(@munch $name [] [] $($items)*);
+        layout!(@from_str $name);
+    };
+    ($name:ident { $($items:tt)* }) => {
+        layout!(@munch $name [] [] $($items)*);
+        layout!(@from_str $name);
+        layout!(@display $name);
+    };
+    (@munch $name:ident [$($fields:ident)*] [$($items:tt)*] $text:literal $(, $($rest:tt)*)?) => {
+        layout!(@munch $name [$($fields)*] [$($items)* (
+            _,
+            |parser: &mut $crate::util::parser::Parser| $crate::util::layout::literal(parser, $text),
+            |_: &$name, f: &mut std::fmt::Formatter<'_>| write!(f, "{}", $text)
+        )] $($($rest)*)?);
+    };
+    (@munch $name:ident [$($fields:ident)*] [$($items:tt)*] $field:ident as $codec:ty $(, $($rest:tt)*)?) => {
+        layout!(@munch $name [$($fields)* $field] [$($items)* (
+            $field,
+            |parser: &mut $crate::util::parser::Parser| <$codec as $crate::util::layout::Codec<_>>::parse(parser),
+            |value: &$name, f: &mut std::fmt::Formatter<'_>| <$codec as $crate::util::layout::Codec<_>>::write(&value.$field, f)
+        )] $($($rest)*)?);
+    };
+    (@munch $name:ident [$($fields:ident)*] [$($items:tt)*] $field:ident $(, $($rest:tt)*)?) => {
+        layout!(@munch $name [$($fields)* $field] [$($items)* (
+            $field,
+            |parser: &mut $crate::util::parser::Parser| $crate::util::layout::Layout::parse(parser),
+            |value: &$name, f: &mut std::fmt::Formatter<'_>| $crate::util::layout::Layout::write(&value.$field, f)
+        )] $($($rest)*)?);
+    };
+    (@munch $name:ident [$($fields:ident)*] [$(($binding:tt, $parse:expr, $write:expr))*]) => {
+        impl $crate::util::layout::Layout for $name {
+            fn parse(parser: &mut $crate::util::parser::Parser) -> Result<Self, String> {
+                $(let $binding = ($parse)(parser)?;)*
+                Ok($name { $($fields),* })
+            }
+
+            fn write(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                $(($write)(self, f)?;)*
+                Ok(())
+            }
+        }
+    };
+    (@from_str $name:ident) => {
+        impl std::str::FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $crate::util::layout::parse_all(s)
+            }
+        }
+    };
+    (@display $name:ident) => {
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                $crate::util::layout::Layout::write(self, f)
+            }
+        }
+    };
+}
+
+pub(crate) use layout;
+
+/// A value with a textual layout: it can be read from a `Parser` and written back in the same format.
+pub trait Layout: Sized {
+    fn parse(parser: &mut Parser) -> Result<Self, String>;
+    fn write(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<T> Layout for T
+    where T: ParseInteger + fmt::Display {
+    fn parse(parser: &mut Parser) -> Result<Self, String> {
+        parser.number()
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// A layout for values of type `T` that is not tied to the type itself, e.g. a list with a specific separator.
+pub trait Codec<T> {
+    fn parse(parser: &mut Parser) -> Result<T, String>;
+    fn write(value: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+/// Single digits without separators, like `987654321`.
+pub struct Digits;
+
+impl Codec<Vec<usize>> for Digits {
+    fn parse(parser: &mut Parser) -> Result<Vec<usize>, String> {
+        let digits = parser.take_while(|c| c.is_ascii_digit())?;
+        if digits.is_empty() { return Err(parser.error("Expected digits")) }
+
+        Ok(digits.chars().map(|c| c as usize - '0' as usize).collect())
+    }
+
+    fn write(value: &Vec<usize>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        value.iter().try_for_each(|d| write!(f, "{}", d))
+    }
+}
+
+/// Booleans written as one character each, like `.##.` with `Flags<'#', '.'>`.
+pub struct Flags<const ON: char, const OFF: char>;
+
+impl<const ON: char, const OFF: char> Codec<Vec<bool>> for Flags<ON, OFF> {
+    fn parse(parser: &mut Parser) -> Result<Vec<bool>, String> {
+        Ok(parser.take_while(|c| c == ON || c == OFF)?.chars().map(|c| c == ON).collect())
+    }
+
+    fn write(value: &Vec<bool>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        value.iter().try_for_each(|v| write!(f, "{}", if *v { ON } else { OFF }))
+    }
+}
+
+/// Values separated by `SEP`. With a space as separator, any amount of whitespace separates the values.
+pub struct List<T, const SEP: char>(PhantomData<T>);
+
+impl<T, const SEP: char> Codec<Vec<T>> for List<T, SEP>
+    where T: Layout {
+    fn parse(parser: &mut Parser) -> Result<Vec<T>, String> {
+        if SEP == ' ' {
+            parser.many(T::parse)
+        } else {
+            let separator = SEP.to_string();
+            parser.sep_by(T::parse, |p| p.literal(&separator))
+        }
+    }
+
+    fn write(value: &Vec<T>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in value.iter().enumerate() {
+            if i > 0 { write!(f, "{}", SEP)?; }
+            item.write(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Matches literal text of a layout. Surrounding whitespace is skipped when parsing, but kept when writing.
+pub fn literal(parser: &mut Parser, text: &str) -> Result<(), String> {
+    match text.trim() {
+        "" => Ok(()),
+        trimmed => parser.literal(trimmed)
+    }
+}
+
+/// Parses a complete value, failing if there is any input left.
+pub fn parse_all<T>(input: &str) -> Result<T, String>
+    where T: Layout {
+    let mut parser = Parser::new(input);
+    let result = T::parse(&mut parser)?;
+    parser.ensure_exhausted()?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::layout::{Digits, Flags, List};
+
+    #[derive(Eq, PartialEq, Copy, Clone, Debug)]
+    enum Turn { Left, Right }
+    layout!(enum Turn { Left = "L", Right = "R" });
+
+    #[derive(Eq, PartialEq, Clone, Debug)]
+    struct Step { turn: Turn, amount: i64 }
+    layout!(Step { turn, amount });
+
+    #[derive(Eq, PartialEq, Clone, Debug)]
+    struct Group { id: u8, values: Vec<usize> }
+    layout!(Group { "<", id, "> ", values as List<usize, ','> });
+
+    #[derive(Eq, PartialEq, Clone, Debug)]
+    struct Panel { lights: Vec<bool>, groups: Vec<Group>, digits: Vec<usize> }
+    layout!(Panel { "[", lights as Flags<'#', '.'>, "] ", groups as List<Group, ' '>, " | ", digits as Digits });
+
+    #[test]
+    fn test_layout_parse() {
+        assert_eq!("R-12".parse(), Ok(Step { turn: Turn::Right, amount: -12 }));
+        assert_eq!("<3>1,2".parse(), Ok(Group { id: 3, values: vec![1, 2] }));
+        assert_eq!("[#.] <1> 5  <2> 6,7 | 0123".parse(), Ok(Panel {
+            lights: vec![true, false],
+            groups: vec![Group { id: 1, values: vec![5] }, Group { id: 2, values: vec![6, 7] }],
+            digits: vec![0, 1, 2, 3],
+        }));
+
+        assert!("X12".parse::<Step>().is_err());
+        assert!("<300> 1".parse::<Group>().is_err());
+        assert!("R12 extra".parse::<Step>().is_err());
+    }
+
+    #[test]
+    fn test_layout_round_trip() {
+        for input in ["L7", "<0> 4,5,6", "[..##] <1> 2 <3> 4,5 | 987"] {
+            let round_trip = match input.chars().next() {
+                Some('[') => input.parse::<Panel>().map(|v| v.to_string()),
+                Some('<') => input.parse::<Group>().map(|v| v.to_string()),
+                _ => input.parse::<Step>().map(|v| v.to_string()),
+            };
+            assert_eq!(round_trip, Ok(input.to_string()));
+        }
+    }
+}
@@ -0,0 +1,429 @@
+// Allow dead_code since this is a util file copied across years. Later in the AoC we might use everything, or not.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, RangeInclusive, Sub};
+use num_traits::{One, PrimInt, Signed, Zero};
+use crate::util::number::Rational;
+
+/// Values that can be used for Gaussian elimination: exact and with division, like `Rational` or `Gf2`.
+pub trait Field: Copy + PartialEq + Zero + One + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self> {}
+
+impl<T> Field for T
+    where T: Copy + PartialEq + Zero + One + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Neg<Output = T> {}
+
+/// A bit in the field with two elements, where addition is xor and multiplication is and.
+/// Toggle puzzles (lights, switches) are linear systems over this field.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Gf2(pub bool);
+
+impl From<bool> for Gf2 {
+    fn from(value: bool) -> Self {
+        Gf2(value)
+    }
+}
+
+impl Zero for Gf2 {
+    fn zero() -> Self {
+        Gf2(false)
+    }
+
+    fn is_zero(&self) -> bool {
+        !self.0
+    }
+}
+
+impl One for Gf2 {
+    fn one() -> Self {
+        Gf2(true)
+    }
+}
+
+impl Add for Gf2 {
+    type Output = Gf2;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Gf2) -> Gf2 {
+        Gf2(self.0 ^ other.0)
+    }
+}
+
+impl Sub for Gf2 {
+    type Output = Gf2;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, other: Gf2) -> Gf2 {
+        Gf2(self.0 ^ other.0)
+    }
+}
+
+impl Mul for Gf2 {
+    type Output = Gf2;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: Gf2) -> Gf2 {
+        Gf2(self.0 && other.0)
+    }
+}
+
+impl Div for Gf2 {
+    type Output = Gf2;
+
+    /// Panics when dividing by zero.
+    fn div(self, other: Gf2) -> Gf2 {
+        assert!(other.0, "attempt to divide by zero");
+        self
+    }
+}
+
+impl Neg for Gf2 {
+    type Output = Gf2;
+
+    fn neg(self) -> Gf2 {
+        self
+    }
+}
+
+impl fmt::Display for Gf2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0 as u8)
+    }
+}
+
+/// Dense matrix, stored row by row.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Copy> Matrix<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Matrix<T>, String> {
+        let cols = rows.first().map(|r| r.len()).unwrap_or(0);
+        if let Some(i) = rows.iter().position(|r| r.len() != cols) {
+            return Err(format!("Not all rows of the matrix are the same length (row {} has {} values, expected {})", i, rows[i].len(), cols));
+        }
+
+        Ok(Matrix { rows: rows.len(), cols, data: rows.into_iter().flatten().collect() })
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, f: impl Fn(usize, usize) -> T) -> Matrix<T> {
+        Matrix { rows, cols, data: (0..rows * cols).map(|i| f(i / cols, i % cols)).collect() }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> T {
+        self.data[row * self.cols + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) {
+        self.data[row * self.cols + col] = value;
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn map<U>(&self, f: impl Fn(T) -> U) -> Matrix<U> {
+        Matrix { rows: self.rows, cols: self.cols, data: self.data.iter().map(|v| f(*v)).collect() }
+    }
+
+    pub fn transpose(&self) -> Matrix<T> {
+        Matrix::from_fn(self.cols, self.rows, |r, c| self.get(c, r))
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for col in 0..self.cols {
+            self.data.swap(a * self.cols + col, b * self.cols + col);
+        }
+    }
+}
+
+impl<T: Copy + Zero + One> Matrix<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix::from_fn(rows, cols, |_, _| T::zero())
+    }
+
+    pub fn identity(size: usize) -> Matrix<T> {
+        Matrix::from_fn(size, size, |r, c| if r == c { T::one() } else { T::zero() })
+    }
+}
+
+impl<T: Copy + Zero + Mul<Output = T>> Matrix<T> {
+    /// The product of this matrix with a column vector.
+    pub fn mul_vector(&self, vector: &[T]) -> Vec<T> {
+        (0..self.rows)
+            .map(|r| self.row(r).iter().zip(vector).fold(T::zero(), |acc, (a, b)| acc + *a * *b))
+            .collect()
+    }
+}
+
+impl<T: Copy + Zero + Mul<Output = T>> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    /// Panics when the dimensions don't match.
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, other.rows, "Cannot multiply a {}x{} matrix with a {}x{} matrix", self.rows, self.cols, other.rows, other.cols);
+
+        Matrix::from_fn(self.rows, other.cols, |r, c| {
+            (0..self.cols).fold(T::zero(), |acc, i| acc + self.get(r, i) * other.get(i, c))
+        })
+    }
+}
+
+impl<T: PrimInt + Signed> Matrix<T> {
+    /// The same integer matrix with rational values, so it can be eliminated exactly.
+    pub fn to_rational(&self) -> Matrix<Rational<T>> {
+        self.map(Rational::from_integer)
+    }
+}
+
+impl<T: Field> Matrix<T> {
+    /// Gauss-Jordan elimination: the reduced row echelon form, together with the column of each pivot.
+    pub fn reduced_row_echelon(&self) -> (Matrix<T>, Vec<usize>) {
+        let mut result = self.clone();
+        let mut pivots = vec![];
+
+        for col in 0..self.cols {
+            let row = pivots.len();
+            let Some(pivot_row) = (row..self.rows).find(|r| !result.get(*r, col).is_zero()) else { continue };
+            result.swap_rows(row, pivot_row);
+
+            let pivot = result.get(row, col);
+            for c in 0..self.cols {
+                result.set(row, c, result.get(row, c) / pivot);
+            }
+
+            for other in (0..self.rows).filter(|r| *r != row) {
+                let factor = result.get(other, col);
+                if factor.is_zero() { continue; }
+
+                for c in 0..self.cols {
+                    result.set(other, c, result.get(other, c) - factor * result.get(row, c));
+                }
+            }
+
+            pivots.push(col);
+            if pivots.len() == self.rows { break; }
+        }
+
+        (result, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.reduced_row_echelon().1.len()
+    }
+
+    /// A basis of all vectors `x` with `self * x = 0`, one vector per free column.
+    pub fn null_space(&self) -> Vec<Vec<T>> {
+        let (reduced, pivots) = self.reduced_row_echelon();
+
+        free_columns(self.cols, &pivots).into_iter()
+            .map(|free| null_vector(&reduced, &pivots, self.cols, free))
+            .collect()
+    }
+
+    /// All solutions of `self * x = b`, or `None` if the system is inconsistent.
+    pub fn solve(&self, b: &[T]) -> Option<Solution<T>> {
+        assert_eq!(b.len(), self.rows, "Right hand side has {} values, expected {}", b.len(), self.rows);
+
+        let augmented = Matrix::from_fn(self.rows, self.cols + 1, |r, c| if c < self.cols { self.get(r, c) } else { b[r] });
+        let (reduced, pivots) = augmented.reduced_row_echelon();
+
+        // A pivot in the last column means 0 = 1
+        if pivots.last() == Some(&self.cols) {
+            return None;
+        }
+
+        let mut particular = vec![T::zero(); self.cols];
+        for (row, col) in pivots.iter().enumerate() {
+            particular[*col] = reduced.get(row, self.cols);
+        }
+
+        let free = free_columns(self.cols, &pivots);
+        let null_space = free.iter().map(|f| null_vector(&reduced, &pivots, self.cols, *f)).collect();
+
+        Some(Solution { particular, free, null_space })
+    }
+}
+
+fn free_columns(cols: usize, pivots: &[usize]) -> Vec<usize> {
+    (0..cols).filter(|c| !pivots.contains(c)).collect()
+}
+
+fn null_vector<T: Field>(reduced: &Matrix<T>, pivots: &[usize], cols: usize, free: usize) -> Vec<T> {
+    let mut vector = vec![T::zero(); cols];
+    vector[free] = T::one();
+    for (row, col) in pivots.iter().enumerate() {
+        vector[*col] = -reduced.get(row, free);
+    }
+
+    vector
+}
+
+/// The solutions of a linear system: `particular` plus any combination of the `null_space` vectors.
+/// Each null space vector belongs to a free variable (in `free`) and has a 1 at that position, so the
+/// values chosen for the free variables are exactly the coefficients of the combination.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Solution<T> {
+    pub particular: Vec<T>,
+    pub free: Vec<usize>,
+    pub null_space: Vec<Vec<T>>,
+}
+
+impl<T: Field> Solution<T> {
+    pub fn is_unique(&self) -> bool {
+        self.free.is_empty()
+    }
+
+    /// The solution with the given values for the free variables (in the order of `free`).
+    pub fn with_free(&self, values: &[T]) -> Vec<T> {
+        let mut result = self.particular.clone();
+        for (value, vector) in values.iter().zip(&self.null_space) {
+            for (r, v) in result.iter_mut().zip(vector) {
+                *r = *r + *value * *v;
+            }
+        }
+
+        result
+    }
+}
+
+impl Solution<Gf2> {
+    /// Every solution; there are 2 to the power of the number of free variables.
+    pub fn enumerate(&self) -> impl Iterator<Item = Vec<Gf2>> + '_ {
+        assert!(self.free.len() < 64, "Too many free variables to enumerate ({})", self.free.len());
+
+        (0..1u64 << self.free.len()).map(|mask| {
+            let values = (0..self.free.len()).map(|i| Gf2(mask >> i & 1 == 1)).collect::<Vec<_>>();
+            self.with_free(&values)
+        })
+    }
+}
+
+impl<T: PrimInt + Signed> Solution<Rational<T>> {
+    /// The integer solutions where every free variable lies in `range`.
+    pub fn integer_solutions(&self, range: RangeInclusive<T>) -> impl Iterator<Item = Vec<T>> + '_ {
+        let (start, end) = (*range.start(), *range.end());
+        let mut values = if start <= end { Some(vec![start; self.free.len()]) } else { None };
+
+        std::iter::from_fn(move || {
+            while let Some(current) = values.take() {
+                // Odometer-style increment of the free variables for the next call
+                let mut next = current.clone();
+                values = next.iter_mut().position(|v| {
+                    if *v < end { *v = *v + T::one(); true } else { *v = start; false }
+                }).map(|_| next);
+
+                let solution = self.with_free(&current.iter().map(|v| Rational::from_integer(*v)).collect::<Vec<_>>());
+                if let Some(integers) = solution.iter().map(|v| v.to_integer()).collect::<Option<Vec<_>>>() {
+                    return Some(integers);
+                }
+            }
+
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::Zero;
+    use crate::util::linalg::{Gf2, Matrix};
+    use crate::util::number::Rational;
+
+    fn rational(rows: Vec<Vec<i64>>) -> Matrix<Rational<i64>> {
+        Matrix::from_rows(rows).unwrap().to_rational()
+    }
+
+    fn r(v: i64) -> Rational<i64> {
+        Rational::from_integer(v)
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let matrix = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!((matrix.rows(), matrix.cols(), matrix.get(1, 0)), (2, 3, 4));
+        assert_eq!(matrix.transpose().row(2), &[3, 6]);
+        assert_eq!(matrix.mul_vector(&[1, 0, -1]), vec![-2, -2]);
+        assert_eq!(&matrix * &Matrix::identity(3), matrix);
+        assert!(Matrix::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    }
+
+    #[test]
+    fn test_reduced_row_echelon() {
+        let matrix = rational(vec![vec![1, 2, -1], vec![2, 4, 1], vec![3, 6, 0]]);
+        let (reduced, pivots) = matrix.reduced_row_echelon();
+
+        assert_eq!(pivots, vec![0, 2]);
+        assert_eq!(reduced, rational(vec![vec![1, 2, 0], vec![0, 0, 1], vec![0, 0, 0]]));
+        assert_eq!(matrix.rank(), 2);
+        assert_eq!(Matrix::<Rational<i64>>::identity(4).rank(), 4);
+        assert_eq!(Matrix::<Rational<i64>>::zeros(2, 3).rank(), 0);
+    }
+
+    #[test]
+    fn test_null_space() {
+        let matrix = rational(vec![vec![1, 2, 3, 4], vec![2, 4, 7, 9]]);
+        let null_space = matrix.null_space();
+
+        assert_eq!(null_space.len(), 2);
+        assert!(null_space.iter().all(|v| matrix.mul_vector(v).iter().all(|x| x.is_zero())));
+    }
+
+    #[test]
+    fn test_solve() {
+        // x + y = 3, x - y = 1
+        let matrix = rational(vec![vec![1, 1], vec![1, -1]]);
+        let solution = matrix.solve(&[r(3), r(1)]).unwrap();
+        assert!(solution.is_unique());
+        assert_eq!(solution.particular, vec![r(2), r(1)]);
+
+        // 2x = 1 has no integer solution, but an exact rational one
+        let solution = rational(vec![vec![2]]).solve(&[r(1)]).unwrap();
+        assert_eq!(solution.particular, vec![Rational::new(1, 2)]);
+        assert_eq!(solution.integer_solutions(0..=10).count(), 0);
+
+        // Inconsistent: x + y = 1, x + y = 2
+        assert!(rational(vec![vec![1, 1], vec![1, 1]]).solve(&[r(1), r(2)]).is_none());
+    }
+
+    #[test]
+    fn test_integer_solutions() {
+        // x + y + z = 4, y - z = 0: z is free, y = z, x = 4 - 2z
+        let matrix = rational(vec![vec![1, 1, 1], vec![0, 1, -1]]);
+        let solution = matrix.solve(&[r(4), r(0)]).unwrap();
+
+        assert_eq!(solution.free, vec![2]);
+        assert_eq!(solution.integer_solutions(0..=3).collect::<Vec<_>>(), vec![vec![4, 0, 0], vec![2, 1, 1], vec![0, 2, 2], vec![-2, 3, 3]]);
+    }
+
+    #[test]
+    fn test_gf2() {
+        // Toggles: button 0 flips lights 0 and 1, button 1 flips 1 and 2, button 2 flips 0 and 2
+        let matrix = Matrix::from_rows(vec![
+            vec![true, false, true],
+            vec![true, true, false],
+            vec![false, true, true],
+        ]).unwrap().map(Gf2);
+
+        assert_eq!(matrix.rank(), 2);
+
+        let target = [Gf2(true), Gf2(false), Gf2(true)];
+        let solution = matrix.solve(&target).unwrap();
+        let solutions = solution.enumerate().collect::<Vec<_>>();
+
+        assert_eq!(solutions.len(), 2);
+        assert!(solutions.iter().all(|s| matrix.mul_vector(s) == target));
+
+        assert!(matrix.solve(&[Gf2(true), Gf2(false), Gf2(false)]).is_none());
+    }
+}
@@ -3,6 +3,29 @@
 
 use num_traits::Num;
 
+mod bigint;
+mod digits;
+mod modular;
+mod primes;
+mod radix;
+mod rational;
+mod sequence;
+
+#[allow(unused_imports)]
+pub use bigint::BigInt;
+#[allow(unused_imports)]
+pub use digits::{concat, digit_count, digits, from_digits, repeat_digits, reverse_digits, DigitIterator};
+#[allow(unused_imports)]
+pub use modular::{crt, discrete_log, extended_gcd, mod_floor, mod_inverse, mod_mul, mod_pow};
+#[allow(unused_imports)]
+pub use primes::{divisor_count, divisor_counts, divisors, factorize, is_prime, primes_in, primes_up_to};
+#[allow(unused_imports)]
+pub use radix::{format_bits, format_radix, from_bits, parse_bits, parse_radix, to_bits};
+#[allow(unused_imports)]
+pub use rational::Rational;
+#[allow(unused_imports)]
+pub use sequence::{extrapolate, extrapolate_at, find_cycle, finite_differences, lagrange, Cycle};
+
 macro_rules! parse_int_impl {
     ($($t:ty, $name: ident, $name_radix: ident)*) => {$(
         #[allow(unused)]
@@ -14,9 +37,29 @@ macro_rules! parse_int_impl {
         pub fn $name_radix(input: &str, radix: u32) -> Result<$t, String> {
             <$t>::from_str_radix(input, radix).map_err(|e| format!("{} ('{}')", e, input))
         }
+
+        impl ParseInteger for $t {
+            const SIGNED: bool = <$t>::MIN != 0;
+
+            fn parse(input: &str) -> Result<Self, String> {
+                $name(input)
+            }
+
+            fn parse_radix(input: &str, radix: u32) -> Result<Self, String> {
+                $name_radix(input, radix)
+            }
+        }
     )*}
 }
 
+/// Primitive integers that can be parsed with the `parse_*` family, so code can be generic over them.
+pub trait ParseInteger: Sized {
+    const SIGNED: bool;
+
+    fn parse(input: &str) -> Result<Self, String>;
+    fn parse_radix(input: &str, radix: u32) -> Result<Self, String>;
+}
+
 parse_int_impl! {
     u8, parse_u8, parse_u8_radix
     u16, parse_u16, parse_u16_radix
@@ -32,19 +75,9 @@ parse_int_impl! {
     isize, parse_isize, parse_isize_radix
 }
 
-pub fn parse_binary(binary: &str) -> usize {
-    let mut result = 0;
-
-    for char in binary.chars() {
-        result <<= 1;
-        match char {
-            '1' => result += 1,
-            '0' => {},
-            _ => panic!("Invalid binary character: {}", char)
-        }
-    }
-
-    result
+/// Parses a string of `0`s and `1`s, failing on other characters or when the value doesn't fit.
+pub fn parse_binary(binary: &str) -> Result<usize, String> {
+    from_bits(&parse_bits(binary)?)
 }
 
 pub fn lcm<T: Num + Copy>(left: T, right: T) -> T {
@@ -62,6 +95,22 @@ pub fn gcd<T: Num + Copy>(a: T, b: T) -> T {
     return gcd(b, a % b);
 }
 
+/// `n` choose `k`, exact. Yields `None` when the result doesn't fit in a `u128`.
+pub fn binomial(n: u64, k: u64) -> Option<u128> {
+    if k > n { return Some(0); }
+
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k as u128 {
+        // result * (n - i) is divisible by i + 1, dividing out the common factor first postpones overflows
+        let (factor, divisor) = (n as u128 - i, i + 1);
+        let common = gcd(result, divisor);
+        result = (result / common).checked_mul(factor / (divisor / common))?;
+    }
+
+    Some(result)
+}
+
 pub trait NumberExtensions<T> {
     fn lcm(&self) -> T;
     fn gcd(&self) -> T;
@@ -86,13 +135,15 @@ impl<T> NumberExtensions<T> for Vec<T> where T: Num + Copy + Clone {
 
 #[cfg(test)]
 mod tests {
-    use crate::util::number::{gcd, lcm, NumberExtensions, parse_binary};
+    use crate::util::number::{binomial, gcd, lcm, NumberExtensions, parse_binary};
 
     #[test]
     fn test_parse_binary() {
-        assert_eq!(parse_binary("0101"), 5);
-        assert_eq!(parse_binary("1111"), 15);
-        assert_eq!(parse_binary("1000000"), 64);
+        assert_eq!(parse_binary("0101"), Ok(5));
+        assert_eq!(parse_binary("1111"), Ok(15));
+        assert_eq!(parse_binary("1000000"), Ok(64));
+        assert!(parse_binary("10201").is_err());
+        assert!(parse_binary(&"1".repeat(65)).is_err());
     }
 
     #[test]
@@ -112,4 +163,15 @@ mod tests {
 
         assert_eq!(4, vec![36, 32, 48].gcd())
     }
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(binomial(5, 2), Some(10));
+        assert_eq!(binomial(5, 0), Some(1));
+        assert_eq!(binomial(5, 6), Some(0));
+        assert_eq!(binomial(0, 0), Some(1));
+        assert_eq!(binomial(67, 33), Some(14_226_520_737_620_288_370));
+        assert_eq!(binomial(130, 65), Some(95_067_625_827_960_698_145_584_333_020_095_113_100));
+        assert_eq!(binomial(140, 70), None);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,503 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Largest power of 10 that fits in a limb, used to convert from and to decimal text.
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+/// Arbitrary precision signed integer, for answers that might not fit in a primitive.
+/// Division and remainder truncate towards zero, like they do for primitives.
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Default)]
+pub struct BigInt {
+    negative: bool,
+    /// Magnitude in base 2^32, least significant limb first, without trailing zeros (so zero has no limbs).
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn one() -> BigInt {
+        BigInt::from(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt { negative: false, limbs: self.limbs.clone() }
+    }
+
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::one();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    /// Quotient and remainder at once, truncating towards zero. Panics when dividing by zero.
+    pub fn div_rem(&self, divisor: &BigInt) -> (BigInt, BigInt) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+
+        let (quotient, remainder) = if divisor.limbs.len() == 1 {
+            let (quotient, remainder) = div_rem_small(&self.limbs, divisor.limbs[0]);
+            (quotient, vec![remainder])
+        } else {
+            div_rem_magnitude(&self.limbs, &divisor.limbs)
+        };
+
+        (
+            BigInt::new(self.negative != divisor.negative, quotient),
+            BigInt::new(self.negative, remainder),
+        )
+    }
+
+    /// The value as an `i128`, if it fits.
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.limbs.len() > 4 { return None; }
+
+        let magnitude = self.limbs.iter().rev().fold(0u128, |acc, limb| (acc << 32) | *limb as u128);
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    fn new(negative: bool, mut limbs: Vec<u32>) -> BigInt {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        BigInt { negative: negative && !limbs.is_empty(), limbs }
+    }
+
+    fn from_magnitude(negative: bool, mut magnitude: u128) -> BigInt {
+        let mut limbs = vec![];
+        while magnitude > 0 {
+            limbs.push(magnitude as u32);
+            magnitude >>= 32;
+        }
+
+        BigInt::new(negative, limbs)
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+
+    for (i, limb) in long.iter().enumerate() {
+        let sum = *limb as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 { result.push(carry as u32); }
+
+    result
+}
+
+/// `a - b`, where the magnitude of `a` must be at least that of `b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+
+    for (i, limb) in a.iter().enumerate() {
+        let mut difference = *limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = if difference < 0 { difference += 1 << 32; 1 } else { 0 };
+        result.push(difference as u32);
+    }
+
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let product = *x as u64 * *y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+
+    result
+}
+
+fn div_rem_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder = 0u64;
+
+    for (i, limb) in a.iter().enumerate().rev() {
+        let current = (remainder << 32) | *limb as u64;
+        quotient[i] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+
+    (quotient, remainder as u32)
+}
+
+/// Binary long division: slower than Knuth's algorithm, but more than fast enough for puzzle sized numbers.
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = vec![];
+
+    for bit in (0..a.len() * 32).rev() {
+        // remainder = remainder * 2 + next bit of a
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for limb in remainder.iter_mut() {
+            let shifted = (*limb >> 31) & 1;
+            *limb = (*limb << 1) | carry;
+            carry = shifted;
+        }
+        if carry > 0 { remainder.push(carry); }
+
+        if cmp_magnitude(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, b);
+            while remainder.last() == Some(&0) { remainder.pop(); }
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+
+    (quotient, remainder)
+}
+
+macro_rules! bigint_from_impl {
+    ($($t:ty: $value:ident => $negative:expr, $magnitude:expr)*) => {$(
+        impl From<$t> for BigInt {
+            fn from($value: $t) -> Self {
+                BigInt::from_magnitude($negative, $magnitude)
+            }
+        }
+
+        impl PartialEq<$t> for BigInt {
+            fn eq(&self, other: &$t) -> bool {
+                self.cmp(&BigInt::from(*other)) == Ordering::Equal
+            }
+        }
+
+        impl PartialOrd<$t> for BigInt {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(self.cmp(&BigInt::from(*other)))
+            }
+        }
+    )*};
+    (unsigned $($t:ty)*) => {
+        bigint_from_impl! { $($t: value => false, value as u128)* }
+    };
+    (signed $($t:ty)*) => {
+        bigint_from_impl! { $($t: value => value < 0, value.unsigned_abs() as u128)* }
+    };
+}
+
+bigint_from_impl! { unsigned u8 u16 u32 u64 u128 usize }
+bigint_from_impl! { signed i8 i16 i32 i64 i128 isize }
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => cmp_magnitude(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.limbs.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.limbs)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_magnitude(&self.limbs, &other.limbs));
+        }
+
+        match cmp_magnitude(&self.limbs, &other.limbs) {
+            Ordering::Less => BigInt::new(other.negative, sub_magnitude(&other.limbs, &self.limbs)),
+            _ => BigInt::new(self.negative, sub_magnitude(&self.limbs, &other.limbs)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(self.negative != other.negative, mul_magnitude(&self.limbs, &other.limbs))
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+/// Forwards the owned (and mixed) variants of an operator, plus its assigning variant, to the one on references.
+macro_rules! bigint_forward_impl {
+    ($($op:ident $method:ident $op_assign:ident $method_assign:ident)*) => {$(
+        impl $op<BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $op<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: &BigInt) -> BigInt {
+                (&self).$method(other)
+            }
+        }
+
+        impl $op<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                self.$method(&other)
+            }
+        }
+
+        impl $op_assign<&BigInt> for BigInt {
+            fn $method_assign(&mut self, other: &BigInt) {
+                *self = (&*self).$method(other);
+            }
+        }
+
+        impl $op_assign<BigInt> for BigInt {
+            fn $method_assign(&mut self, other: BigInt) {
+                *self = (&*self).$method(&other);
+            }
+        }
+    )*}
+}
+
+bigint_forward_impl! {
+    Add add AddAssign add_assign
+    Sub sub SubAssign sub_assign
+    Mul mul MulAssign mul_assign
+}
+
+impl Div<BigInt> for BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: BigInt) -> BigInt {
+        &self / &other
+    }
+}
+
+impl Rem<BigInt> for BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: BigInt) -> BigInt {
+        &self % &other
+    }
+}
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |acc, v| acc + v)
+    }
+}
+
+impl<'a> Sum<&'a BigInt> for BigInt {
+    fn sum<I: Iterator<Item = &'a BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |acc, v| acc + v)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Split into groups of 9 decimal digits, least significant first
+        let mut groups = vec![];
+        let mut remaining = self.limbs.clone();
+        while !remaining.is_empty() {
+            let (quotient, group) = div_rem_small(&remaining, DECIMAL_BASE);
+            groups.push(group);
+            remaining = BigInt::new(false, quotient).limbs;
+        }
+
+        let mut digits = match groups.pop() {
+            Some(most_significant) => most_significant.to_string(),
+            None => "0".to_string(),
+        };
+        for group in groups.iter().rev() {
+            digits += &format!("{:0width$}", group, width = DECIMAL_DIGITS);
+        }
+
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        if digits.is_empty() {
+            return Err(format!("cannot parse integer from empty string ('{}')", s));
+        }
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(format!("invalid digit '{}' found in string ('{}')", c, s));
+        }
+
+        let mut limbs: Vec<u32> = vec![];
+        for chunk in digits.as_bytes().chunks(DECIMAL_DIGITS) {
+            let value = chunk.iter().fold(0u64, |acc, d| acc * 10 + (d - b'0') as u64);
+            let mut carry = value;
+            let scale = 10u64.pow(chunk.len() as u32);
+            for limb in limbs.iter_mut() {
+                let current = *limb as u64 * scale + carry;
+                *limb = current as u32;
+                carry = current >> 32;
+            }
+            if carry > 0 { limbs.push(carry as u32); }
+        }
+
+        Ok(BigInt::new(negative, limbs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::number::BigInt;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in ["0", "7", "-7", "4294967296", "-18446744073709551616", "123456789012345678901234567890123456789"] {
+            assert_eq!(big(s).to_string(), s);
+        }
+
+        assert_eq!(big("+0042"), 42);
+        assert_eq!(big("-0"), BigInt::zero());
+        assert_eq!(format!("{:>6}", BigInt::from(-12)), "   -12");
+        assert!("".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+        assert!("12a".parse::<BigInt>().is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = big("123456789012345678901234567890");
+        let b = big("-987654321098765432109876543210");
+
+        assert_eq!(&a + &b, big("-864197532086419753208641975320"));
+        assert_eq!(&a - &b, big("1111111110111111111011111111100"));
+        assert_eq!(&a * &b, big("-121932631137021795226185032733622923332237463801111263526900"));
+        assert_eq!(&a - &a, 0);
+        assert_eq!(BigInt::from(u64::MAX) + BigInt::one(), big("18446744073709551616"));
+        assert_eq!(BigInt::from(2).pow(100), big("1267650600228229401496703205376"));
+        assert_eq!(BigInt::from(-3).pow(3), -27);
+        assert_eq!([1, 2, 3].into_iter().map(BigInt::from).sum::<BigInt>(), 6);
+
+        let mut total = BigInt::from(u128::MAX);
+        total += BigInt::from(u128::MAX);
+        total *= &BigInt::from(2);
+        assert_eq!(total, big("1361129467683753853853498429727072845820"));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let a = big("121932631137021795226185032733622923332237463801111263526907");
+        let b = big("987654321098765432109876543210");
+        assert_eq!(a.div_rem(&b), (big("123456789012345678901234567890"), big("7")));
+
+        // Truncates towards zero, like primitives
+        for (x, y) in [(7, 2), (-7, 2), (7, -2), (-7, -2), (1, 5)] {
+            let (quotient, remainder) = BigInt::from(x).div_rem(&BigInt::from(y));
+            assert_eq!((quotient, remainder), (BigInt::from(x / y), BigInt::from(x % y)));
+        }
+
+        assert_eq!(big("-100000000000000000000") / BigInt::from(7), big("-14285714285714285714"));
+        assert_eq!(big("100000000000000000000") % BigInt::from(7), 2);
+    }
+
+    #[test]
+    fn test_compare() {
+        assert!(big("-5") < big("3"));
+        assert!(big("-5") < -4);
+        assert!(big("100000000000000000000000000000000000000000") > u128::MAX);
+        assert!(big("-100000000000000000000000000000000000000000") < i128::MIN);
+        assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+        assert_eq!(BigInt::from(u128::MAX).to_i128(), None);
+        assert_eq!(BigInt::from(usize::MAX), usize::MAX);
+    }
+}
@@ -0,0 +1,138 @@
+use num_traits::PrimInt;
+
+/// Iterator over the digits of a non-negative number, most significant first (or least significant
+/// first when reversed). Zero has a single digit.
+#[derive(Clone, Debug)]
+pub struct DigitIterator<T> {
+    value: T,
+    base: T,
+    /// Place value of the most significant digit not yet yielded from the front.
+    high: T,
+    /// Place value of the least significant digit not yet yielded from the back.
+    low: T,
+    remaining: u32,
+}
+
+impl<T: PrimInt> Iterator for DigitIterator<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 { return None; }
+
+        let digit = self.value / self.high % self.base;
+        self.high = self.high / self.base;
+        self.remaining -= 1;
+        Some(digit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
+
+impl<T: PrimInt> DoubleEndedIterator for DigitIterator<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.remaining == 0 { return None; }
+
+        let digit = self.value / self.low % self.base;
+        // The place value past the most significant digit might not fit in T, but it's never used
+        self.low = self.low.checked_mul(&self.base).unwrap_or(self.low);
+        self.remaining -= 1;
+        Some(digit)
+    }
+}
+
+impl<T: PrimInt> ExactSizeIterator for DigitIterator<T> {}
+
+/// The digits of `n` in the given base, most significant first. Use `.rev()` for least significant first.
+pub fn digits<T: PrimInt>(n: T, base: T) -> DigitIterator<T> {
+    let count = digit_count(n, base);
+
+    DigitIterator { value: n, base, high: base.pow(count - 1), low: T::one(), remaining: count }
+}
+
+/// The amount of digits of a non-negative `n` in the given base (1 for zero).
+pub fn digit_count<T: PrimInt>(n: T, base: T) -> u32 {
+    assert!(base > T::one(), "base must be at least 2");
+
+    let mut count = 1;
+    let mut remaining = n / base;
+    while remaining > T::zero() {
+        count += 1;
+        remaining = remaining / base;
+    }
+
+    count
+}
+
+/// Builds a number from its digits, most significant first.
+pub fn from_digits<T: PrimInt>(digits: impl IntoIterator<Item = T>, base: T) -> T {
+    digits.into_iter().fold(T::zero(), |acc, digit| acc * base + digit)
+}
+
+/// `n` with its digits in reverse order (trailing zeros disappear, e.g. 120 becomes 21).
+pub fn reverse_digits<T: PrimInt>(n: T, base: T) -> T {
+    from_digits(digits(n, base).rev(), base)
+}
+
+/// `b` written after `a`, e.g. 12 and 345 become 12345.
+pub fn concat<T: PrimInt>(a: T, b: T, base: T) -> T {
+    a * base.pow(digit_count(b, base)) + b
+}
+
+/// The digits of `block` repeated `times` times, e.g. 12 three times becomes 121212.
+pub fn repeat_digits<T: PrimInt>(block: T, times: u32, base: T) -> T {
+    let shift = base.pow(digit_count(block, base));
+
+    (0..times).fold(T::zero(), |acc, _| acc * shift + block)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::number::{concat, digit_count, digits, from_digits, repeat_digits, reverse_digits};
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digits(1234, 10).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(digits(1234, 10).rev().collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+        assert_eq!(digits(0u8, 10).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(digits(10u8, 2).collect::<Vec<_>>(), vec![1, 0, 1, 0]);
+        assert_eq!(digits(0xbeefu32, 16).collect::<Vec<_>>(), vec![11, 14, 14, 15]);
+        assert_eq!(digits(u64::MAX, 10).next_back(), Some(5));
+        assert_eq!(digits(u64::MAX, 10).len(), 20);
+
+        // Taking from both ends
+        let mut iter = digits(12345, 10);
+        assert_eq!((iter.next(), iter.next_back(), iter.next(), iter.next_back()), (Some(1), Some(5), Some(2), Some(4)));
+        assert_eq!((iter.next(), iter.next_back()), (Some(3), None));
+    }
+
+    #[test]
+    fn test_digit_count() {
+        assert_eq!(digit_count(0, 10), 1);
+        assert_eq!(digit_count(9, 10), 1);
+        assert_eq!(digit_count(10, 10), 2);
+        assert_eq!(digit_count(u64::MAX, 10), 20);
+        assert_eq!(digit_count(255u8, 2), 8);
+    }
+
+    #[test]
+    fn test_from_digits() {
+        assert_eq!(from_digits([1, 2, 3], 10), 123);
+        assert_eq!(from_digits([1, 0, 1, 0], 2), 10);
+        assert_eq!(from_digits(Vec::<u32>::new(), 10), 0);
+        assert_eq!(from_digits(digits(987_654_321u64, 10), 10), 987_654_321);
+    }
+
+    #[test]
+    fn test_reverse_concat_repeat() {
+        assert_eq!(reverse_digits(1234, 10), 4321);
+        assert_eq!(reverse_digits(120, 10), 21);
+        assert_eq!(concat(12, 345, 10), 12345);
+        assert_eq!(concat(12, 0, 10), 120);
+        assert_eq!(concat(0b10u8, 0b11, 2), 0b1011);
+        assert_eq!(repeat_digits(12, 3, 10), 121212);
+        assert_eq!(repeat_digits(7u64, 1, 10), 7);
+        assert_eq!(repeat_digits(5, 0, 10), 0);
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use num_traits::{PrimInt, Signed};
+
+/// Solves `a * x + b * y = gcd(a, b)`, returning `(gcd, x, y)`.
+pub fn extended_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+
+    while r != T::zero() {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < T::zero() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The remainder of `a / m` in `0..m`, also for negative `a`.
+pub fn mod_floor<T: PrimInt>(a: T, m: T) -> T {
+    let r = a % m;
+    if r < T::zero() { r + m } else { r }
+}
+
+/// The `x` for which `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime and `m` is positive.
+pub fn mod_inverse<T: PrimInt>(a: T, m: T) -> Option<T> {
+    let m_wide = m.to_i128().filter(|m| *m > 0)?;
+    let (g, x, _) = extended_gcd(mod_floor(a, m).to_i128()?, m_wide);
+
+    if g == 1 { T::from(mod_floor(x, m_wide)) } else { None }
+}
+
+/// `a * b mod m` without overflowing, for any modulus that fits in a `u128`. Panics if `m` is not positive.
+pub fn mod_mul<T: PrimInt>(a: T, b: T, m: T) -> T {
+    let m_wide = m.to_u128().filter(|m| *m > 0).expect("Modulus must be positive");
+    let a = mod_floor(a, m).to_u128().unwrap_or_default();
+    let b = mod_floor(b, m).to_u128().unwrap_or_default();
+
+    T::from(mul_mod_u128(a, b, m_wide)).expect("Result is smaller than the modulus")
+}
+
+fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if m <= u64::MAX as u128 {
+        return (a % m) * (b % m) % m;
+    }
+
+    // Double-and-add, as the product itself might not fit in 128 bits
+    let (mut a, mut b, mut result) = (a % m, b, 0u128);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod_u128(result, a, m);
+        }
+        a = add_mod_u128(a, a, m);
+        b >>= 1;
+    }
+
+    result
+}
+
+fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b { a - (m - b) } else { a + b }
+}
+
+/// `base ^ exp mod m` by repeated squaring. Panics if `exp` is negative or `m` is not positive.
+pub fn mod_pow<T: PrimInt>(base: T, exp: T, m: T) -> T {
+    let m_wide = m.to_u128().filter(|m| *m > 0).expect("Modulus must be positive");
+    let mut base = mod_floor(base, m).to_u128().unwrap_or_default();
+    let mut exp = exp.to_u128().expect("Exponent must not be negative");
+    let mut result = 1 % m_wide;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_u128(result, base, m_wide);
+        }
+        base = mul_mod_u128(base, base, m_wide);
+        exp >>= 1;
+    }
+
+    T::from(result).expect("Result is smaller than the modulus")
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)`, returning `(x, lcm of the moduli)`.
+/// The moduli don't need to be coprime. Yields `None` when the congruences contradict each other
+/// (or when a modulus is not positive, or the combined modulus does not fit in `T`).
+pub fn crt<T: PrimInt>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut result: (i128, i128) = (0, 1);
+
+    for (residue, modulus) in congruences {
+        let (r2, m2) = (residue.to_i128()?, modulus.to_i128().filter(|m| *m > 0)?);
+        let (r1, m1) = result;
+        let r2 = mod_floor(r2, m2);
+
+        let (g, p, _) = extended_gcd(m1, m2);
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+
+        // x = r1 + m1 * k, with k = (r2 - r1) / g * p (mod m2 / g)
+        let step = m2 / g;
+        let k = mod_mul(mod_floor((r2 - r1) / g, step), mod_floor(p, step), step);
+        let lcm = m1.checked_mul(step)?;
+
+        result = (mod_floor(r1 + mod_mul(m1, k, lcm), lcm), lcm);
+    }
+
+    Some((T::from(result.0)?, T::from(result.1)?))
+}
+
+/// The smallest `x >= 0` for which `base ^ x ≡ target (mod m)`, using baby-step giant-step.
+/// Works for any positive modulus, also when `base` and `m` are not coprime.
+pub fn discrete_log<T: PrimInt>(base: T, target: T, m: T) -> Option<T> {
+    let mut m = m.to_u128().filter(|m| *m > 0)?;
+    let mut base = mod_floor(base, T::from(m)?).to_u128()?;
+    let mut target = mod_floor(target, T::from(m)?).to_u128()?;
+
+    if m == 1 {
+        return T::from(0);
+    }
+
+    // Divide out common factors of base and m, so base becomes invertible
+    let mut offset = 0u128;
+    let mut factor = 1 % m;
+    loop {
+        let g = gcd_u128(base, m);
+        if g == 1 { break; }
+        if target == factor { return T::from(offset); }
+        if target % g != 0 { return None; }
+
+        target /= g;
+        m /= g;
+        offset += 1;
+        factor = mul_mod_u128(factor, base / g, m);
+        base %= m;
+    }
+
+    let n = m.isqrt() + 1;
+
+    // Baby steps: target * base^j for 0 <= j < n, keeping the largest j to find the smallest x
+    let mut baby_steps = HashMap::new();
+    let mut current = target % m;
+    for j in 0..=n {
+        baby_steps.insert(current, j);
+        current = mul_mod_u128(current, base, m);
+    }
+
+    // Giant steps: factor * base^(n * i)
+    let giant = mod_pow(base, n, m);
+    let mut current = factor;
+    for i in 1..=n {
+        current = mul_mod_u128(current, giant, m);
+        if let Some(j) = baby_steps.get(&current) {
+            return T::from(i * n - j + offset);
+        }
+    }
+
+    None
+}
+
+fn gcd_u128(a: u128, b: u128) -> u128 {
+    if b == 0 { a } else { gcd_u128(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::number::{crt, discrete_log, extended_gcd, mod_floor, mod_inverse, mod_mul, mod_pow};
+
+    #[test]
+    fn test_extended_gcd() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(extended_gcd(-12i64, 18), (6, 1, 1));
+        assert_eq!(extended_gcd(7, 0), (7, 1, 0));
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_floor(-7, 5), 3);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3i64, 11), Some(7));
+        assert_eq!(mod_inverse(10u64, 17), Some(12));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(5u8, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX - 1, 3, u64::MAX), u64::MAX - 1);
+        assert_eq!(mod_mul(u64::MAX - 1, u64::MAX - 1, u64::MAX), 1);
+
+        // Fermat's little theorem for the Mersenne prime 2^127 - 1 needs 128-bit safe multiplication
+        let prime = (1u128 << 127) - 1;
+        assert_eq!(mod_pow(3, prime - 1, prime), 1);
+    }
+
+    #[test]
+    fn test_mod_inverse_zero_modulus() {
+        assert_eq!(mod_inverse(3, 0), None);
+        assert_eq!(mod_inverse(3, -7), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Modulus must be positive")]
+    fn test_mod_mul_zero_modulus() {
+        mod_mul(3, 4, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Modulus must be positive")]
+    fn test_mod_pow_zero_modulus() {
+        mod_pow(3, 4, 0);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(2u64, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(-1i64, 4), (1, 6)]), Some((7, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt::<i32>(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(2, 3), (1, 0)]), None);
+        assert_eq!(crt(&[(2, 3), (1, -5)]), None);
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(2, 3, 5), Some(3));
+        assert_eq!(discrete_log(3u64, 13, 17), Some(4));
+        assert_eq!(discrete_log(5, 1, 7), Some(0));
+        assert_eq!(discrete_log(2, 0, 8), Some(3));
+        assert_eq!(discrete_log(2, 4, 12), Some(2));
+        assert_eq!(discrete_log(2, 3, 8), None);
+        assert_eq!(discrete_log(2, 3, 0), None);
+        assert_eq!(discrete_log(7u64, mod_pow(7, 123_456, 1_000_000_007), 1_000_000_007), Some(123_456));
+    }
+}
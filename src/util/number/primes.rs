@@ -0,0 +1,241 @@
+use std::ops::RangeInclusive;
+use crate::util::number::{gcd, mod_mul, mod_pow};
+
+/// Amount of numbers sieved at once by `primes_in`.
+const SEGMENT_SIZE: u64 = 1 << 16;
+
+/// All primes up to and including `limit`, using the sieve of Eratosthenes.
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut result = vec![];
+
+    for n in 2..=limit {
+        if composite[n] { continue; }
+
+        result.push(n as u64);
+        for multiple in (n * n..=limit).step_by(n) {
+            composite[multiple] = true;
+        }
+    }
+
+    result
+}
+
+/// The primes in `range`, sieved one segment at a time so large (and far away) ranges don't need much memory.
+pub fn primes_in(range: RangeInclusive<u64>) -> impl Iterator<Item = u64> {
+    let (start, end) = (*range.start().max(&2), *range.end());
+    let base_primes = if start <= end { primes_up_to(end.isqrt()) } else { vec![] };
+
+    (start..=end).step_by(SEGMENT_SIZE as usize).flat_map(move |low| {
+        sieve_segment(low, low.saturating_add(SEGMENT_SIZE - 1).min(end), &base_primes)
+    })
+}
+
+/// The numbers in `low..=high` that aren't a multiple of any of the (sorted) `base_primes`, other than the prime itself.
+fn sieve_segment(low: u64, high: u64, base_primes: &[u64]) -> Vec<u64> {
+    let mut composite = vec![false; (high - low + 1) as usize];
+
+    for p in base_primes.iter().take_while(|p| *p * *p <= high) {
+        // Near the top of the u64 range, the next multiple might not fit; then there's none in this segment
+        let Some(first) = low.div_ceil(*p).checked_mul(*p) else { continue };
+        for multiple in (first.max(p * p)..=high).step_by(*p as usize) {
+            composite[(multiple - low) as usize] = true;
+        }
+    }
+
+    composite.into_iter().enumerate().filter(|(_, c)| !c).map(|(i, _)| low + i as u64).collect()
+}
+
+/// Deterministic Miller–Rabin primality test; these bases are sufficient for every `u64`.
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 { return false; }
+    if let Some(p) = BASES.iter().find(|p| n.is_multiple_of(**p)) {
+        return n == *p;
+    }
+
+    let trailing = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> trailing;
+
+    'witness: for base in BASES {
+        let mut x = mod_pow(base, odd, n);
+        if x == 1 || x == n - 1 { continue; }
+
+        for _ in 1..trailing {
+            x = mod_mul(x, x, n);
+            if x == n - 1 { continue 'witness; }
+        }
+
+        return false;
+    }
+
+    true
+}
+
+/// Prime factorization of `n` as `(prime, exponent)` pairs, ordered by prime. Uses Pollard's rho for large factors.
+/// Both 0 and 1 yield no factors.
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    if n == 0 { return vec![]; }
+
+    let mut factors = vec![];
+    let mut remaining = n;
+
+    // Small factors are cheaper to find by trial division
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while remaining.is_multiple_of(p) && remaining > 1 {
+            factors.push(p);
+            remaining /= p;
+        }
+    }
+
+    let mut stack = vec![remaining];
+    while let Some(m) = stack.pop() {
+        if m == 1 { continue; }
+        if is_prime(m) {
+            factors.push(m);
+        } else {
+            let divisor = pollard_rho(m);
+            stack.push(divisor);
+            stack.push(m / divisor);
+        }
+    }
+
+    factors.sort();
+    let mut result: Vec<(u64, u32)> = vec![];
+    for factor in factors {
+        match result.last_mut() {
+            Some((p, exponent)) if *p == factor => *exponent += 1,
+            _ => result.push((factor, 1)),
+        }
+    }
+
+    result
+}
+
+/// Finds a non-trivial divisor of a composite `n` with Pollard's rho, retrying with another polynomial on failure.
+fn pollard_rho(n: u64) -> u64 {
+    if n.is_multiple_of(2) { return 2; }
+
+    for c in 1u64.. {
+        let f = |x: u64| ((mod_mul(x, x, n) as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+
+        if d != n { return d; }
+    }
+
+    unreachable!("Pollard's rho always finds a divisor of a composite number")
+}
+
+/// All divisors of `n` in increasing order (`n` itself included).
+pub fn divisors(n: u64) -> impl Iterator<Item = u64> {
+    let mut result = vec![1];
+
+    for (p, exponent) in factorize(n) {
+        let current = result.clone();
+        let mut power = 1;
+        for _ in 0..exponent {
+            power *= p;
+            result.extend(current.iter().map(|d| d * power));
+        }
+    }
+
+    // Zero is divisible by anything, so rather than yielding everything, yield nothing
+    if n == 0 { result.clear(); }
+    result.sort();
+    result.into_iter()
+}
+
+/// The number of divisors of `n` (0 for 0, like `divisors`).
+pub fn divisor_count(n: u64) -> u64 {
+    if n == 0 { return 0; }
+
+    factorize(n).iter().map(|(_, exponent)| *exponent as u64 + 1).product()
+}
+
+/// The number of divisors of every number up to and including `limit` (index 0 is unused).
+pub fn divisor_counts(limit: usize) -> Vec<u32> {
+    let mut counts = vec![0; limit + 1];
+
+    for d in 1..=limit {
+        for multiple in (d..=limit).step_by(d) {
+            counts[multiple] += 1;
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+    use crate::util::number::{divisor_count, divisor_counts, divisors, factorize, is_prime, primes_in, primes_up_to};
+    use crate::util::number::primes::sieve_segment;
+
+    #[test]
+    fn test_primes_up_to() {
+        assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_up_to(1), Vec::<u64>::new());
+        assert_eq!(primes_up_to(100_000).len(), 9592);
+    }
+
+    #[test]
+    fn test_primes_in() {
+        assert_eq!(primes_in(0..=30).collect::<Vec<_>>(), primes_up_to(30));
+        assert_eq!(primes_in(1_000_000_000..=1_000_000_100).collect::<Vec<_>>(), vec![1_000_000_007, 1_000_000_009, 1_000_000_021, 1_000_000_033, 1_000_000_087, 1_000_000_093, 1_000_000_097]);
+        assert_eq!(primes_in(1..=1_000_000).count(), 78498);
+        assert_eq!(primes_in(RangeInclusive::new(10, 9)).count(), 0);
+    }
+
+    #[test]
+    fn test_sieve_segment_near_u64_max() {
+        // Sieving all of primes_in(..=u64::MAX) needs every prime below 2^32, so test a segment with a few of them.
+        // The next multiple of the largest prime below 2^32 lies past u64::MAX.
+        let base_primes = [2, 3, 5, 7, 4_294_967_291];
+        let low = u64::MAX - 20;
+
+        let expected = (low..=u64::MAX).filter(|n| base_primes.iter().all(|p| n % p != 0)).collect::<Vec<_>>();
+        assert_eq!(sieve_segment(low, u64::MAX, &base_primes), expected);
+        assert!(!sieve_segment(u64::MAX - 30, u64::MAX - 20, &base_primes).contains(&(u64::MAX - 24)));
+    }
+
+    #[test]
+    fn test_is_prime() {
+        assert!(!is_prime(0));
+        assert!(!is_prime(1));
+        assert!(is_prime(2));
+        assert!(is_prime(1_000_000_007));
+        assert!(!is_prime(3_215_031_751)); // strong pseudoprime to bases 2, 3, 5 and 7
+        assert!(is_prime(18_446_744_073_709_551_557)); // largest u64 prime
+        assert!(!is_prime(18_446_744_073_709_551_557 - 2));
+
+        let sieved = primes_up_to(10_000);
+        assert!((0..=10_000).all(|n| is_prime(n) == sieved.binary_search(&n).is_ok()));
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(1), Vec::<(u64, u32)>::new());
+        assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(1_000_000_007 * 998_244_353), vec![(998_244_353, 1), (1_000_000_007, 1)]);
+        assert_eq!(factorize(u64::MAX), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6_700_417, 1)]);
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(divisors(12).collect::<Vec<_>>(), vec![1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(1).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(divisors(0).count(), 0);
+        assert_eq!(divisor_count(360), 24);
+
+        let counts = divisor_counts(100);
+        assert!((1..=100).all(|n| counts[n] as u64 == divisor_count(n as u64)));
+    }
+}
@@ -0,0 +1,118 @@
+use num_traits::PrimInt;
+use crate::util::number::{digits, ParseInteger};
+
+/// Parses `input` as a number in the given radix (2 to 36), failing on invalid digits, overflow or an
+/// unsupported radix instead of panicking.
+pub fn parse_radix<T: ParseInteger>(input: &str, radix: u32) -> Result<T, String> {
+    check_radix(radix)?;
+
+    T::parse_radix(input, radix)
+}
+
+/// Formats `value` in the given radix (2 to 36), using lowercase letters for digits above 9.
+pub fn format_radix<T: PrimInt>(value: T, radix: u32) -> Result<String, String> {
+    check_radix(radix)?;
+
+    // Every primitive fits in either an i128 or a u128
+    let (negative, magnitude) = match value.to_u128() {
+        Some(magnitude) => (false, magnitude),
+        None => (true, value.to_i128().ok_or("Value does not fit in 128 bits")?.unsigned_abs()),
+    };
+
+    let digits = digits(magnitude, radix as u128)
+        .map(|d| char::from_digit(d as u32, radix).expect("Digit is smaller than the radix"));
+
+    Ok(if negative { "-".chars().chain(digits).collect() } else { digits.collect() })
+}
+
+/// Parses a string of `0`s and `1`s into bits, most significant first.
+pub fn parse_bits(input: &str) -> Result<Vec<bool>, String> {
+    input.chars().map(|c| match c {
+        '1' => Ok(true),
+        '0' => Ok(false),
+        _ => Err(format!("Invalid binary character: {} ('{}')", c, input))
+    }).collect()
+}
+
+/// Writes bits as a string of `0`s and `1`s, most significant first.
+pub fn format_bits(bits: &[bool]) -> String {
+    bits.iter().map(|b| if *b { '1' } else { '0' }).collect()
+}
+
+/// The value of bits, most significant first. Fails if the value doesn't fit in `T`.
+pub fn from_bits<T: PrimInt>(bits: &[bool]) -> Result<T, String> {
+    let two = T::one() + T::one();
+
+    bits.iter().try_fold(T::zero(), |acc, bit| {
+        acc.checked_mul(&two)
+            .and_then(|v| if *bit { v.checked_add(&T::one()) } else { Some(v) })
+            .ok_or_else(|| format!("Bits {} do not fit in {} bits", format_bits(bits), T::zero().count_zeros()))
+    })
+}
+
+/// The lowest `width` bits of a non-negative `value`, most significant first. Fails if `value` needs more bits.
+pub fn to_bits<T: PrimInt>(value: T, width: usize) -> Result<Vec<bool>, String> {
+    let needed = (T::zero().count_zeros() - value.leading_zeros()) as usize;
+    if value < T::zero() || needed > width {
+        return Err(format!("Value {} does not fit in {} bits", value.to_i128().unwrap_or_default(), width));
+    }
+
+    Ok((0..width).rev().map(|i| i < needed && (value >> i) & T::one() == T::one()).collect())
+}
+
+fn check_radix(radix: u32) -> Result<(), String> {
+    if (2..=36).contains(&radix) {
+        Ok(())
+    } else {
+        Err(format!("Radix must be between 2 and 36, got {}", radix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::number::{format_bits, format_radix, from_bits, parse_bits, parse_radix, to_bits};
+
+    #[test]
+    fn test_parse_radix() {
+        assert_eq!(parse_radix::<u8>("ff", 16), Ok(255));
+        assert_eq!(parse_radix::<i64>("-zz", 36), Ok(-1295));
+        assert_eq!(parse_radix::<u128>(&"1".repeat(128), 2), Ok(u128::MAX));
+        assert!(parse_radix::<u8>("100", 16).is_err());
+        assert!(parse_radix::<u32>("12", 2).is_err());
+        assert!(parse_radix::<u32>("12", 37).is_err());
+        assert!(parse_radix::<u32>("0", 1).is_err());
+    }
+
+    #[test]
+    fn test_format_radix() {
+        assert_eq!(format_radix(255u8, 16), Ok("ff".to_string()));
+        assert_eq!(format_radix(0, 2), Ok("0".to_string()));
+        assert_eq!(format_radix(-1295i64, 36), Ok("-zz".to_string()));
+        assert_eq!(format_radix(i128::MIN, 16), Ok(format!("-8{}", "0".repeat(31))));
+        assert_eq!(format_radix(u128::MAX, 2), Ok("1".repeat(128)));
+        assert!(format_radix(5, 40).is_err());
+
+        for radix in 2..=36 {
+            assert_eq!(format_radix(-123_456i32, radix).and_then(|s| parse_radix(&s, radix)), Ok(-123_456i32));
+        }
+    }
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(parse_bits("0101"), Ok(vec![false, true, false, true]));
+        assert!(parse_bits("01a1").is_err());
+        assert_eq!(format_bits(&[true, false, true]), "101");
+
+        assert_eq!(from_bits::<u8>(&[false, true, false, true]), Ok(5));
+        assert_eq!(from_bits::<u8>(&[false; 20]), Ok(0));
+        assert_eq!(from_bits::<u8>(&[true; 8]), Ok(255));
+        assert!(from_bits::<u8>(&[true; 9]).is_err());
+        assert!(from_bits::<u64>(&parse_bits(&format!("1{}", "0".repeat(64))).unwrap()).is_err());
+
+        assert_eq!(to_bits(5u8, 4), Ok(vec![false, true, false, true]));
+        assert_eq!(to_bits(0u64, 2), Ok(vec![false, false]));
+        assert_eq!(to_bits(255u8, 10).map(|b| format_bits(&b)), Ok("0011111111".to_string()));
+        assert!(to_bits(8, 3).is_err());
+        assert!(to_bits(-1, 64).is_err());
+    }
+}
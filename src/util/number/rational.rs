@@ -0,0 +1,273 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+use num_traits::{One, PrimInt, Signed, Zero};
+use crate::util::number::{gcd, ParseInteger};
+
+/// Exact fraction `numer / denom`, always stored in lowest terms with a positive denominator,
+/// so equal values have equal representations.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Rational<T> {
+    numer: T,
+    denom: T,
+}
+
+impl<T: PrimInt + Signed> Rational<T> {
+    /// Panics when `denom` is zero, like integer division does.
+    pub fn new(numer: T, denom: T) -> Rational<T> {
+        assert!(!denom.is_zero(), "denominator of a rational must not be zero");
+
+        let divisor = gcd(numer, denom).abs();
+        let sign = if denom < T::zero() { -T::one() } else { T::one() };
+
+        Rational { numer: sign * numer / divisor, denom: sign * denom / divisor }
+    }
+
+    pub fn from_integer(value: T) -> Rational<T> {
+        Rational { numer: value, denom: T::one() }
+    }
+
+    pub fn numer(&self) -> T {
+        self.numer
+    }
+
+    pub fn denom(&self) -> T {
+        self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == T::one()
+    }
+
+    /// The value as an integer, if it is one.
+    pub fn to_integer(self) -> Option<T> {
+        if self.is_integer() { Some(self.numer) } else { None }
+    }
+
+    /// Largest integer not greater than the value.
+    pub fn floor(&self) -> T {
+        let quotient = self.numer / self.denom;
+        if self.numer % self.denom < T::zero() { quotient - T::one() } else { quotient }
+    }
+
+    /// Smallest integer not less than the value.
+    pub fn ceil(&self) -> T {
+        let quotient = self.numer / self.denom;
+        if self.numer % self.denom > T::zero() { quotient + T::one() } else { quotient }
+    }
+
+    pub fn abs(&self) -> Rational<T> {
+        Rational { numer: self.numer.abs(), denom: self.denom }
+    }
+
+    /// `1 / self`. Panics for zero.
+    pub fn recip(&self) -> Rational<T> {
+        Rational::new(self.denom, self.numer)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.numer.to_f64().unwrap_or(f64::NAN) / self.denom.to_f64().unwrap_or(f64::NAN)
+    }
+}
+
+impl<T: PrimInt + Signed> From<T> for Rational<T> {
+    fn from(value: T) -> Self {
+        Rational::from_integer(value)
+    }
+}
+
+impl<T: PrimInt + Signed> Default for Rational<T> {
+    fn default() -> Self {
+        Rational::zero()
+    }
+}
+
+impl<T: PrimInt + Signed> Zero for Rational<T> {
+    fn zero() -> Self {
+        Rational::from_integer(T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+}
+
+impl<T: PrimInt + Signed> One for Rational<T> {
+    fn one() -> Self {
+        Rational::from_integer(T::one())
+    }
+}
+
+impl<T: PrimInt + Signed> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so cross-multiplying keeps the order
+        let divisor = gcd(self.denom, other.denom);
+        (self.numer * (other.denom / divisor)).cmp(&(other.numer * (self.denom / divisor)))
+    }
+}
+
+impl<T: PrimInt + Signed> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PrimInt + Signed> Neg for Rational<T> {
+    type Output = Rational<T>;
+
+    fn neg(self) -> Rational<T> {
+        Rational { numer: -self.numer, denom: self.denom }
+    }
+}
+
+impl<T: PrimInt + Signed> Add for Rational<T> {
+    type Output = Rational<T>;
+
+    fn add(self, other: Rational<T>) -> Rational<T> {
+        // Scale to the lcm of the denominators rather than their product, to postpone overflows
+        let divisor = gcd(self.denom, other.denom);
+        let numer = self.numer * (other.denom / divisor) + other.numer * (self.denom / divisor);
+
+        Rational::new(numer, self.denom / divisor * other.denom)
+    }
+}
+
+impl<T: PrimInt + Signed> Sub for Rational<T> {
+    type Output = Rational<T>;
+
+    fn sub(self, other: Rational<T>) -> Rational<T> {
+        self + -other
+    }
+}
+
+impl<T: PrimInt + Signed> Mul for Rational<T> {
+    type Output = Rational<T>;
+
+    fn mul(self, other: Rational<T>) -> Rational<T> {
+        // Cross-reduce first, to postpone overflows
+        let a = gcd(self.numer, other.denom).abs();
+        let b = gcd(other.numer, self.denom).abs();
+
+        Rational::new((self.numer / a) * (other.numer / b), (self.denom / b) * (other.denom / a))
+    }
+}
+
+impl<T: PrimInt + Signed> Div for Rational<T> {
+    type Output = Rational<T>;
+
+    /// Panics when dividing by zero.
+    fn div(self, other: Rational<T>) -> Rational<T> {
+        Mul::mul(self, other.recip())
+    }
+}
+
+macro_rules! rational_assign_impl {
+    ($($op_assign:ident $method_assign:ident $op:tt)*) => {$(
+        impl<T: PrimInt + Signed> $op_assign for Rational<T> {
+            fn $method_assign(&mut self, other: Rational<T>) {
+                *self = *self $op other;
+            }
+        }
+    )*}
+}
+
+rational_assign_impl! {
+    AddAssign add_assign +
+    SubAssign sub_assign -
+    MulAssign mul_assign *
+    DivAssign div_assign /
+}
+
+impl<T: PrimInt + Signed + fmt::Display> fmt::Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl<T: PrimInt + Signed + ParseInteger> FromStr for Rational<T> {
+    type Err = String;
+
+    /// Parses either an integer (`-3`) or a fraction (`6/-4`), which is normalized.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((numer, denom)) => {
+                let denom = T::parse(denom.trim())?;
+                if denom.is_zero() {
+                    return Err(format!("Denominator must not be zero ('{}')", s));
+                }
+
+                Ok(Rational::new(T::parse(numer.trim())?, denom))
+            },
+            None => Ok(Rational::from_integer(T::parse(s.trim())?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::number::Rational;
+
+    fn r(numer: i64, denom: i64) -> Rational<i64> {
+        Rational::new(numer, denom)
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(r(6, -4), r(-3, 2));
+        assert_eq!((r(6, -4).numer(), r(6, -4).denom()), (-3, 2));
+        assert_eq!(r(0, -5), Rational::from_integer(0));
+        assert_eq!(r(10, 5).to_integer(), Some(2));
+        assert_eq!(r(10, 4).to_integer(), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(r(1, 2) + r(1, 3), r(5, 6));
+        assert_eq!(r(1, 2) - r(5, 6), r(-1, 3));
+        assert_eq!(r(2, 3) * r(9, -4), r(-3, 2));
+        assert_eq!(r(2, 3) / r(4, 9), r(3, 2));
+        assert_eq!(-r(2, 3), r(-2, 3));
+        assert_eq!(r(-2, 3).recip(), r(-3, 2));
+
+        let mut total = Rational::from(1i64);
+        total += r(1, 2);
+        total *= r(4, 3);
+        assert_eq!(total, Rational::from(2));
+
+        // Intermediate products would overflow without reducing first
+        let big = r(i64::MAX, 3);
+        assert_eq!(big * r(3, i64::MAX), r(1, 1));
+    }
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!((r(7, 2).floor(), r(7, 2).ceil()), (3, 4));
+        assert_eq!((r(-7, 2).floor(), r(-7, 2).ceil()), (-4, -3));
+        assert_eq!((r(4, 2).floor(), r(4, 2).ceil()), (2, 2));
+        assert_eq!(r(-1, 4).to_f64(), -0.25);
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+        assert!(r(7, 3) > Rational::from(2));
+        assert_eq!(vec![r(1, 2), r(-3, 4), r(1, 3)].into_iter().max(), Some(r(1, 2)));
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("6/-4".parse(), Ok(r(-3, 2)));
+        assert_eq!("12".parse(), Ok(r(12, 1)));
+        assert!("1/0".parse::<Rational<i64>>().is_err());
+        assert!("a/2".parse::<Rational<i64>>().is_err());
+
+        assert_eq!(r(-3, 2).to_string(), "-3/2");
+        assert_eq!(r(8, 4).to_string(), "2");
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use num_traits::{PrimInt, Signed, Zero};
+use crate::util::number::Rational;
+
+/// The difference table of `samples`: the samples themselves, their differences, the differences of those,
+/// etc. Stops at the first row that is all zeros (or has a single value).
+pub fn finite_differences<T: PrimInt + Signed>(samples: &[T]) -> Vec<Vec<T>> {
+    let mut table = vec![samples.to_vec()];
+
+    while let Some(last) = table.last() && last.len() > 1 && last.iter().any(|v| !v.is_zero()) {
+        let next = last.windows(2).map(|w| w[1] - w[0]).collect();
+        table.push(next);
+    }
+
+    table
+}
+
+/// The next `count` values of the sequence, assuming the last row of its difference table stays constant.
+/// For samples of a polynomial with a degree lower than the amount of samples, this is exact.
+pub fn extrapolate<T: PrimInt + Signed>(samples: &[T], count: usize) -> Vec<T> {
+    let mut lasts = finite_differences(samples).iter().filter_map(|row| row.last().copied()).collect::<Vec<_>>();
+    if lasts.is_empty() { return vec![T::zero(); count]; }
+
+    (0..count).map(|_| {
+        for i in (0..lasts.len().saturating_sub(1)).rev() {
+            lasts[i] = lasts[i] + lasts[i + 1];
+        }
+        lasts[0]
+    }).collect()
+}
+
+/// The value at position `x` of the polynomial through `samples` (taken at positions 0, 1, 2, ...), using
+/// Newton's forward differences. Any `x` takes the same time, and negative positions extrapolate backwards.
+pub fn extrapolate_at<T: PrimInt + Signed>(samples: &[T], x: T) -> T {
+    let mut result = T::zero();
+    let mut binomial = T::one(); // x choose k, exact for any integer x
+
+    for (k, row) in finite_differences(samples).iter().enumerate() {
+        let Some(first) = row.first() else { break };
+        result = result + *first * binomial;
+
+        let k = T::from(k).expect("Amount of samples fits in T");
+        binomial = binomial * (x - k) / (k + T::one());
+    }
+
+    result
+}
+
+/// The value at `x` of the polynomial through the given `(x, y)` points, with Lagrange interpolation.
+/// Yields `None` if two points share the same `x`.
+pub fn lagrange<T: PrimInt + Signed>(points: &[(T, T)], x: T) -> Option<Rational<T>> {
+    let mut result = Rational::zero();
+
+    for (i, (xi, yi)) in points.iter().enumerate() {
+        let mut term = Rational::from_integer(*yi);
+        for (j, (xj, _)) in points.iter().enumerate() {
+            if i == j { continue; }
+            if xi == xj { return None; }
+
+            term *= Rational::new(x - *xj, *xi - *xj);
+        }
+        result += term;
+    }
+
+    Some(result)
+}
+
+/// The states of a deterministic process up to the first repeated state: `history[offset..]` repeats forever.
+#[derive(Clone, Debug)]
+pub struct Cycle<S> {
+    pub offset: usize,
+    pub length: usize,
+    pub history: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /// Maps step `n` to `(cycles, step)`: step `n` has the same state as the earlier `step`, after `cycles`
+    /// full cycles. Useful when some value (like a score) grows by a fixed amount every cycle.
+    pub fn reduce(&self, n: usize) -> (usize, usize) {
+        if n < self.offset {
+            (0, n)
+        } else {
+            ((n - self.offset) / self.length, self.offset + (n - self.offset) % self.length)
+        }
+    }
+
+    /// The state after `n` steps, however large `n` is.
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.history[self.reduce(n).1]
+    }
+}
+
+/// Runs `step` from `start` until a state repeats.
+pub fn find_cycle<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    let mut seen = HashMap::new();
+    let mut history = vec![];
+    let mut current = start;
+
+    loop {
+        if let Some(offset) = seen.get(&current) {
+            return Cycle { offset: *offset, length: history.len() - offset, history };
+        }
+
+        seen.insert(current.clone(), history.len());
+        let next = step(&current);
+        history.push(current);
+        current = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::number::{extrapolate, extrapolate_at, find_cycle, finite_differences, lagrange, Rational};
+
+    #[test]
+    fn test_finite_differences() {
+        assert_eq!(finite_differences(&[1, 4, 9, 16]), vec![vec![1, 4, 9, 16], vec![3, 5, 7], vec![2, 2], vec![0]]);
+        assert_eq!(finite_differences(&[5, 5]), vec![vec![5, 5], vec![0]]);
+        assert_eq!(finite_differences::<i64>(&[]), vec![Vec::<i64>::new()]);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15], 2), vec![18, 21]);
+        assert_eq!(extrapolate(&[1, 3, 6, 10, 15, 21], 1), vec![28]);
+        assert_eq!(extrapolate(&[10, 13, 16, 21, 30, 45], 1), vec![68]);
+        assert_eq!(extrapolate(&[7], 3), vec![7, 7, 7]);
+    }
+
+    #[test]
+    fn test_extrapolate_at() {
+        // n^2 + n + 41
+        let samples = [41i128, 43, 47, 53];
+        assert_eq!(extrapolate_at(&samples, 4), 61);
+        assert_eq!(extrapolate_at(&samples, -1), 41);
+        assert_eq!(extrapolate_at(&samples, 1_000_000_000), 1_000_000_001_000_000_041);
+        assert_eq!(extrapolate_at(&[10, 13, 16, 21, 30, 45], -1), 5);
+        assert_eq!(extrapolate_at(&[10, 13, 16, 21, 30, 45], 6), 68);
+    }
+
+    #[test]
+    fn test_lagrange() {
+        // 2x^2 - 3 through points that are not evenly spaced
+        let points = [(-2i64, 5), (1, -1), (5, 47)];
+        assert_eq!(lagrange(&points, 3), Some(Rational::from(15)));
+        assert_eq!(lagrange(&[(0i64, 0), (2, 1)], 1), Some(Rational::new(1, 2)));
+        assert_eq!(lagrange(&[(1i64, 0), (1, 1)], 1), None);
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        // 3 -> 9 -> 27 -> 81 -> 43 -> 29 -> 87 -> 61 -> 83 -> 49 -> 47 -> 41 -> 23 -> 69 -> 7 -> 21 -> 63 -> 89 -> 67 -> 1 -> 3 (mod 100)
+        let cycle = find_cycle(3u64, |v| v * 3 % 100);
+        assert_eq!((cycle.offset, cycle.length), (0, 20));
+        assert_eq!(*cycle.state_at(1_000_000_000_001), 9);
+
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 2 -> ...
+        let cycle = find_cycle(0, |v| if *v == 4 { 2 } else { v + 1 });
+        assert_eq!((cycle.offset, cycle.length), (2, 3));
+        assert_eq!(cycle.reduce(1), (0, 1));
+        assert_eq!(cycle.reduce(10), (2, 4));
+        assert_eq!(*cycle.state_at(10), 4);
+    }
+}
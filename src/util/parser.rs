@@ -1,111 +1,791 @@
-use std::cmp::min;
-use crate::util::number::parse_usize;
+use std::fmt::Display;
+use std::str::FromStr;
+use crate::util::number::{parse_usize, ParseInteger};
+
+/// Amount of characters shown before and after the error position in error excerpts.
+const EXCERPT_CONTEXT: usize = 40;
+
+/// A single option for `Parser::alt`.
+pub type Alternative<'a, T> = &'a dyn Fn(&mut Parser) -> Result<T, String>;
+
+/// Parses a single line according to a format string, in which every `{}` is converted to a value using `FromStr`.
+/// The values are returned as a tuple, e.g. `let (w, h): (usize, usize) = scan!(line, "{}x{}")?;`.
+/// The whole input has to match the format.
+#[allow(unused)]
+macro_rules! scan {
+    ($input:expr, $format:expr) => {{
+        let mut parser = $crate::util::parser::Parser::new($input);
+        parser.scan($format).and_then(|values| parser.ensure_exhausted().map(|_| values))
+    }};
+}
+
+#[allow(unused)]
+pub(crate) use scan;
+
+/// Tuples of values that can be the result of `Parser::scan`.
+pub trait FromCaptures: Sized {
+    const COUNT: usize;
+
+    /// Converts the captured text of every placeholder. On failure, yields the index of the failing placeholder.
+    fn from_captures(captures: &[String]) -> Result<Self, (usize, String)>;
+}
+
+macro_rules! from_captures_impl {
+    ($($count:literal => ($($t:ident $i:tt),+))*) => {$(
+        impl<$($t),+> FromCaptures for ($($t,)+)
+            where $($t: FromStr, <$t as FromStr>::Err: Display),+ {
+            const COUNT: usize = $count;
+
+            fn from_captures(captures: &[String]) -> Result<Self, (usize, String)> {
+                Ok(($(captures[$i].parse::<$t>().map_err(|e| ($i, e.to_string()))?,)+))
+            }
+        }
+    )*}
+}
+
+from_captures_impl! {
+    1 => (A 0)
+    2 => (A 0, B 1)
+    3 => (A 0, B 1, C 2)
+    4 => (A 0, B 1, C 2, D 3)
+    5 => (A 0, B 1, C 2, D 3, E 4)
+    6 => (A 0, B 1, C 2, D 3, E 4, F 5)
+    7 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6)
+    8 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7)
+}
+
+/// Which whitespace a `Parser` skips before each token.
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+pub enum Whitespace {
+    /// Skip any whitespace, including newlines.
+    #[default]
+    All,
+    /// Skip spaces and tabs, but treat newlines as significant.
+    Spaces,
+    /// Skip nothing, for formats where every character (column) matters.
+    None,
+}
+
+impl Whitespace {
+    fn skips(&self, c: char) -> bool {
+        match self {
+            Whitespace::All => c.is_whitespace(),
+            Whitespace::Spaces => c.is_whitespace() && c != '\n' && c != '\r',
+            Whitespace::None => false,
+        }
+    }
+}
+
+/// A position to return to with `Parser::restore`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Checkpoint {
+    position: usize,
+    whitespace: Whitespace,
+}
 
 pub struct Parser {
     input: String,
-    position: usize
+    position: usize,
+    whitespace: Whitespace,
+    /// Section of the puzzle input being parsed, for error messages.
+    section: Option<usize>,
 }
 
 #[allow(unused)]
 impl Parser {
     pub fn new<T>(input: T) -> Self
         where T: ToString {
-        Parser { input: input.to_string(), position: 0 }
+        Parser { input: input.to_string(), position: 0, whitespace: Whitespace::All, section: None }
+    }
+
+    pub fn with_section(mut self, section: usize) -> Self {
+        self.section = Some(section);
+        self
+    }
+
+    pub fn with_whitespace(mut self, whitespace: Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Changes how whitespace is handled from here on, e.g. to parse a column-aligned part of the input.
+    pub fn set_whitespace(&mut self, whitespace: Whitespace) {
+        self.whitespace = whitespace;
+    }
+
+    /// The part of the input that has not been consumed yet. Note that `position` is a byte offset.
+    fn rest(&self) -> &str {
+        &self.input[self.position..]
+    }
+
+    /// Formats an error for the current position, pointing out the line and column it happened at.
+    pub fn error<T>(&self, message: T) -> String
+        where T: std::fmt::Display {
+        let before = &self.input[..self.position];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = self.rest().find('\n').map(|i| self.position + i).unwrap_or(self.input.len());
+
+        let line_number = before.matches('\n').count() + 1;
+        let column = self.column();
+        let line = self.input[line_start..line_end].trim_end_matches('\r');
+
+        // Keep the excerpt readable for very long lines (e.g. a single line input)
+        let skip = column.saturating_sub(EXCERPT_CONTEXT);
+        let excerpt = line.chars().skip(skip).take(EXCERPT_CONTEXT * 2).collect::<String>();
+        let prefix = if skip > 0 { "..." } else { "" };
+
+        let section = self.section.map(|s| format!("section {}, ", s)).unwrap_or_default();
+
+        format!("{}line {}, column {}: {}\n    {}{}\n    {}^", section, line_number, column + 1, message, prefix, excerpt, " ".repeat(prefix.len() + column - skip))
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { position: self.position, whitespace: self.whitespace }
+    }
+
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.position = checkpoint.position;
+        self.whitespace = checkpoint.whitespace;
+    }
+
+    /// Applies `item`, rewinding to where it started if it fails.
+    pub fn try_parse<T, F>(&mut self, item: F) -> Result<T, String>
+        where F: FnOnce(&mut Parser) -> Result<T, String> {
+        let checkpoint = self.checkpoint();
+        item(self).inspect_err(|_| self.restore(checkpoint))
+    }
+
+    /// Whether `item` would match here. Never consumes any input.
+    pub fn lookahead<T, F>(&mut self, item: F) -> bool
+        where F: FnOnce(&mut Parser) -> Result<T, String> {
+        let checkpoint = self.checkpoint();
+        let matches = item(self).is_ok();
+        self.restore(checkpoint);
+
+        matches
+    }
+
+    /// The next character after any skipped whitespace, without consuming anything.
+    pub fn peek_char(&self) -> Option<char> {
+        self.rest().chars().find(|c| !self.whitespace.skips(*c))
+    }
+
+    /// Skips whitespace and applies `item`. When it fails, the skipped whitespace is not consumed either.
+    fn token<T, F>(&mut self, item: F) -> Result<T, String>
+        where F: FnOnce(&mut Parser) -> Result<T, String> {
+        self.try_parse(|p| {
+            p.skip_whitespace();
+            item(p)
+        })
     }
 
     fn skip_whitespace(&mut self) {
-        self.position += self.input[self.position..].chars().take_while(|c| c.is_whitespace()).count()
+        self.skip(self.whitespace)
     }
 
-    pub fn literal(&mut self, literal: &str) -> Result<(), String> {
-        self.skip_whitespace();
+    fn skip(&mut self, whitespace: Whitespace) {
+        self.position += self.rest().chars().take_while(|c| whitespace.skips(*c)).map(|c| c.len_utf8()).sum::<usize>()
+    }
 
-        let actual = &self.input[self.position..min(self.position+literal.len(), self.input.len())];
-        if actual != literal {
-            Err(format!("Expected '{}' to match '{}' ('{}':{})", actual, literal, self.input, self.position))
-        } else {
-            self.position += literal.len();
+    /// The (zero-based) column of the current position, counted in characters.
+    pub fn column(&self) -> usize {
+        let before = &self.input[..self.position];
+        before[before.rfind('\n').map(|i| i + 1).unwrap_or(0)..].chars().count()
+    }
+
+    /// Consumes exactly one space, without skipping any whitespace first.
+    pub fn space(&mut self) -> Result<(), String> {
+        if self.rest().starts_with(' ') {
+            self.position += 1;
             Ok(())
+        } else {
+            Err(self.error("Expected a space"))
         }
     }
 
+    /// Consumes a line ending (`\n` or `\r\n`). Spaces before it are skipped unless whitespace is significant.
+    pub fn newline(&mut self) -> Result<(), String> {
+        self.try_parse(|p| {
+            if p.whitespace != Whitespace::None {
+                p.skip(Whitespace::Spaces);
+            }
+
+            if p.rest().starts_with('\n') {
+                p.position += 1;
+                Ok(())
+            } else if p.rest().starts_with("\r\n") {
+                p.position += 2;
+                Ok(())
+            } else {
+                Err(p.error("Expected a newline"))
+            }
+        })
+    }
+
+    pub fn literal(&mut self, literal: &str) -> Result<(), String> {
+        self.token(|p| {
+            if p.rest().starts_with(literal) {
+                p.position += literal.len();
+                Ok(())
+            } else {
+                let actual = p.rest().chars().take(literal.chars().count()).collect::<String>();
+                Err(p.error(format!("Expected '{}', found '{}'", literal, actual)))
+            }
+        })
+    }
+
     pub fn one_of(&mut self, options: Vec<&'static str>) -> Result<&'static str, String> {
-        for option in &options {
-            if self.literal(option).is_ok() {
-                return Ok(option)
+        self.token(|p| {
+            for option in &options {
+                if p.literal(option).is_ok() {
+                    return Ok(*option)
+                }
             }
-        }
 
-        Err(format!("Expected one of {} ('{}':{})", options.iter().map(|o| format!("'{}'",o)).collect::<Vec<_>>().join(", "), self.input, self.position))
+            Err(p.error(format!("Expected one of {}", options.iter().map(|o| format!("'{}'",o)).collect::<Vec<_>>().join(", "))))
+        })
     }
 
     pub fn usize(&mut self) -> Result<usize, String> {
-        self.skip_whitespace();
+        self.number()
+    }
 
-        let mut result = 0;
+    pub fn isize(&mut self) -> Result<isize, String> {
+        self.number()
+    }
+
+    /// Parses a decimal integer of any primitive type, with an optional `+` or `-` sign.
+    pub fn number<T>(&mut self) -> Result<T, String>
+        where T: ParseInteger {
+        self.token(|p| {
+            let sign = p.sign::<T>()?;
+            p.digits(sign, 10)
+        })
+    }
 
-        // consume at least one numeric character
-        let numbers: Vec<_> = self.input.chars().skip(self.position)
-            .take_while(|c| c.is_numeric())
-            .collect();
-        if numbers.len() == 0 { return Err(format!("Expected to find a number. ('{}':{})", self.input, self.position)) }
+    /// Like `number`, but also accepts hexadecimal (`0x`), octal (`0o`) and binary (`0b`) numbers.
+    pub fn prefixed_number<T>(&mut self) -> Result<T, String>
+        where T: ParseInteger {
+        self.token(|p| {
+            let sign = p.sign::<T>()?;
+            let rest = p.rest();
+            let radix = match rest.get(..2) {
+                Some("0x") | Some("0X") => 16,
+                Some("0o") | Some("0O") => 8,
+                Some("0b") | Some("0B") => 2,
+                _ => 10
+            };
+
+            // Only treat it as a prefix if there are digits following it, so a plain "0" still works
+            if radix != 10 && rest[2..].chars().next().is_some_and(|c| c.is_digit(radix)) {
+                p.position += 2;
+                p.digits(sign, radix)
+            } else {
+                p.digits(sign, 10)
+            }
+        })
+    }
 
-        for char in numbers.iter() {
-            result *= 10;
-            result += parse_usize(char.to_string().as_str())?;
+    fn sign<T>(&mut self) -> Result<&'static str, String>
+        where T: ParseInteger {
+        match self.rest().chars().next() {
+            Some('-') if !T::SIGNED => Err(self.error("Expected a non-negative number")),
+            Some('-') => { self.position += 1; Ok("-") }
+            Some('+') => { self.position += 1; Ok("") }
+            _ => Ok("")
         }
+    }
+
+    fn digits<T>(&mut self, sign: &str, radix: u32) -> Result<T, String>
+        where T: ParseInteger {
+        let len = self.rest().chars().take_while(|c| c.is_digit(radix)).count();
+        if len == 0 { return Err(self.error("Expected to find a number")) }
 
-        self.position += numbers.len();
+        // Digits are ASCII, so the amount of characters equals the amount of bytes
+        let digits = format!("{}{}", sign, &self.rest()[..len]);
+        let result = T::parse_radix(&digits, radix).map_err(|e| self.error(e))?;
+
+        self.position += len;
         Ok(result)
     }
 
-    pub fn isize(&mut self) -> Result<isize, String> {
-        self.skip_whitespace();
+    pub fn digit(&mut self) -> Result<usize, String> {
+        self.token(|p| {
+            let result = match p.rest().chars().next() {
+                Some(value) if value.is_ascii_digit() => parse_usize(value.to_string().as_str())?,
+                _ => return Err(p.error("Expected digit"))
+            };
 
-        let modifier = if self.input.chars().nth(self.position) == Some('-') {
-            self.position += 1;
-            -1
-        } else {
-            1
-        };
+            p.position += 1;
+            Ok(result)
+        })
+    }
 
-        Ok(modifier * (self.usize()?) as isize)
+    pub fn str(&mut self, len: usize) -> Result<String, String> {
+        self.token(|p| {
+            let result: String = p.rest().chars().take(len).collect();
+            let count = result.chars().count();
+            if count != len {
+                Err(p.error(format!("Expected to read {} chars, but only got {}", len, count)))
+            } else {
+                p.position += result.len();
+                Ok(result)
+            }
+        })
     }
 
-    pub fn digit(&mut self) -> Result<usize, String> {
-        self.skip_whitespace();
+    /// Consumes characters as long as `predicate` holds, which may be none at all.
+    pub fn take_while<F>(&mut self, predicate: F) -> Result<String, String>
+        where F: Fn(char) -> bool {
+        self.token(|p| {
+            let result = p.rest().chars().take_while(|c| predicate(*c)).collect::<String>();
+            p.position += result.len();
+            Ok(result)
+        })
+    }
 
-        let result = match self.input.chars().nth(self.position) {
-            Some(value) if value.is_numeric() => parse_usize(value.to_string().as_str())?,
-            _ => return Err(format!("Expected digit, found '{}'", self.input))
-        };
+    /// Consumes everything up to (but not including) `end`, which has to be present.
+    pub fn take_until(&mut self, end: &str) -> Result<String, String> {
+        self.token(|p| {
+            let Some(len) = p.rest().find(end) else { return Err(p.error(format!("Expected to find '{}'", end))) };
+            let result = p.rest()[..len].to_string();
+            p.position += len;
+            Ok(result)
+        })
+    }
 
-        self.position += 1;
+    /// A run of non-whitespace characters.
+    pub fn word(&mut self) -> Result<String, String> {
+        self.token(|p| {
+            let result = p.take_while(|c| !c.is_whitespace())?;
+            if result.is_empty() { Err(p.error("Expected a word")) } else { Ok(result) }
+        })
+    }
+
+    /// A letter or underscore, followed by any amount of letters, digits and underscores.
+    pub fn identifier(&mut self) -> Result<String, String> {
+        self.token(|p| {
+            match p.rest().chars().next() {
+                Some(c) if c.is_alphabetic() || c == '_' => p.take_while(|c| c.is_alphanumeric() || c == '_'),
+                _ => Err(p.error("Expected an identifier"))
+            }
+        })
+    }
+
+    /// Everything up to the end of the current line. The line ending itself is not consumed.
+    pub fn rest_of_line(&mut self) -> Result<String, String> {
+        if self.whitespace != Whitespace::None {
+            self.skip(Whitespace::Spaces);
+        }
+
+        let len = self.rest().find('\n').unwrap_or(self.rest().len());
+        let result = self.rest()[..len].trim_end_matches('\r').to_string();
+        self.position += len;
         Ok(result)
     }
 
-    pub fn str(&mut self, len: usize) -> Result<String, String> {
-        self.skip_whitespace();
+    /// A single character out of `set`.
+    pub fn char_in(&mut self, set: &str) -> Result<char, String> {
+        self.token(|p| {
+            match p.rest().chars().next() {
+                Some(c) if set.contains(c) => {
+                    p.position += c.len_utf8();
+                    Ok(c)
+                }
+                _ => Err(p.error(format!("Expected one of the characters '{}'", set)))
+            }
+        })
+    }
 
-        let result: Vec<_> = self.input.chars().skip(self.position).take(len).collect();
-        if result.len() != len {
-            Err(format!("Expected to read {} chars, but only got {}. ('{}':{})", len, result.len(), self.input, self.position))
-        } else {
-            self.position += len;
-            Ok(result.iter().collect())
+    /// Matches the rest of the current line against a format string (see `scan!`). Every `{}` captures the text
+    /// up to the literal text following it, or up to the end of the line for a trailing placeholder.
+    pub fn scan<T>(&mut self, format: &str) -> Result<T, String>
+        where T: FromCaptures {
+        let segments = format.split("{}").collect::<Vec<_>>();
+        let count = segments.len() - 1;
+        if count != T::COUNT {
+            return Err(format!("Format '{}' has {} placeholders, but {} values were requested", format, count, T::COUNT));
         }
+
+        self.try_parse(|p| {
+            let leading = segments[0].trim_start();
+            if !leading.is_empty() {
+                p.literal(leading)?;
+            }
+
+            let mut captures = vec![];
+            let mut starts = vec![];
+
+            for (i, next) in segments[1..].iter().enumerate() {
+                p.skip_whitespace();
+
+                let rest = p.rest();
+                let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+                let end = match line.find(next) {
+                    _ if next.is_empty() && i + 1 < count => return Err(format!("Placeholder {} in format '{}' is not followed by any text", i + 1, format)),
+                    _ if next.is_empty() => line.len(),
+                    Some(end) => end,
+                    None => return Err(p.error(format!("Placeholder {}: expected '{}' after it", i + 1, next)))
+                };
+
+                captures.push(line[..end].trim().to_string());
+                starts.push(p.position);
+                p.position += end + next.len();
+            }
+
+            T::from_captures(&captures).map_err(|(i, e)| {
+                p.position = starts[i];
+                p.error(format!("Placeholder {} ('{}'): {}", i + 1, captures[i], e))
+            })
+        })
+    }
+
+    /// Applies `item` as often as possible (zero or more times). A failing attempt does not consume input.
+    pub fn many<T, F>(&mut self, mut item: F) -> Result<Vec<T>, String>
+        where F: FnMut(&mut Parser) -> Result<T, String> {
+        let mut result = vec![];
+
+        loop {
+            let start = self.position;
+            match self.try_parse(&mut item) {
+                Ok(_) if self.position == start => {
+                    return Err(self.error("Repeated parser succeeded without consuming input"));
+                }
+                Ok(value) => result.push(value),
+                Err(_) => return Ok(result)
+            }
+        }
+    }
+
+    /// Like `many`, but fails (with the error of `item`) if it does not match at least once.
+    pub fn many1<T, F>(&mut self, mut item: F) -> Result<Vec<T>, String>
+        where F: FnMut(&mut Parser) -> Result<T, String> {
+        let first = self.try_parse(&mut item)?;
+        let rest = self.many(item)?;
+
+        Ok(vec![first].into_iter().chain(rest).collect())
     }
 
+    /// Parses zero or more `item`s separated by `separator`. Once a separator matched, the next item must match too.
+    pub fn sep_by<T, S, F, G>(&mut self, mut item: F, mut separator: G) -> Result<Vec<T>, String>
+        where F: FnMut(&mut Parser) -> Result<T, String>, G: FnMut(&mut Parser) -> Result<S, String> {
+        let Some(first) = self.optional(&mut item)? else { return Ok(vec![]) };
+        let mut result = vec![first];
+
+        loop {
+            if self.try_parse(&mut separator).is_err() {
+                return Ok(result);
+            }
+
+            result.push(item(self)?);
+        }
+    }
+
+    /// Tries `item`, yielding `None` (without consuming input) when it does not match.
+    pub fn optional<T, F>(&mut self, item: F) -> Result<Option<T>, String>
+        where F: FnOnce(&mut Parser) -> Result<T, String> {
+        Ok(self.try_parse(item).ok())
+    }
+
+    /// Parses `open`, `inner` and `close` in order, yielding only the result of `inner`.
+    pub fn delimited<T, O, C, F, G, H>(&mut self, mut open: F, mut inner: G, mut close: H) -> Result<T, String>
+        where F: FnMut(&mut Parser) -> Result<O, String>,
+              G: FnMut(&mut Parser) -> Result<T, String>,
+              H: FnMut(&mut Parser) -> Result<C, String> {
+        open(self)?;
+        let result = inner(self)?;
+        close(self)?;
+
+        Ok(result)
+    }
+
+    /// Parses `inner` followed by `terminator`, yielding only the result of `inner`.
+    pub fn terminated<T, E, F, G>(&mut self, mut inner: F, mut terminator: G) -> Result<T, String>
+        where F: FnMut(&mut Parser) -> Result<T, String>, G: FnMut(&mut Parser) -> Result<E, String> {
+        let result = inner(self)?;
+        terminator(self)?;
+
+        Ok(result)
+    }
+
+    /// Yields the result of the first option that matches. Failed options do not consume input.
+    pub fn alt<T>(&mut self, options: &[Alternative<T>]) -> Result<T, String> {
+        let mut errors = vec![];
+
+        for option in options {
+            match self.try_parse(option) {
+                Ok(value) => return Ok(value),
+                Err(e) => errors.push(e.lines().next().unwrap_or_default().to_string())
+            }
+        }
+
+        Err(self.error(format!("None of the alternatives matched ({})", errors.join("; "))))
+    }
+
+    /// Applies `item` exactly `count` times.
+    pub fn repeat<T, F>(&mut self, count: usize, mut item: F) -> Result<Vec<T>, String>
+        where F: FnMut(&mut Parser) -> Result<T, String> {
+        (0..count).map(|_| item(self)).collect()
+    }
+
+    /// Whether only (trailing) whitespace is left, regardless of the whitespace mode.
     pub fn is_exhausted(&self) -> bool {
-        let rest = &self.input[self.position..self.input.len()];
-        rest.is_empty() || rest.chars().all(|c| c.is_whitespace())
+        self.rest().trim().is_empty()
     }
     
-    pub fn ensure_exhausted(&self) -> Result<(), String> {
+    pub fn ensure_exhausted(&mut self) -> Result<(), String> {
         if self.is_exhausted() { 
             Ok(())
         } else {
-            Err(format!("Unexpected extra content: '{}'", self.input[self.position..].trim()))
+            // Point at the extra content rather than the whitespace before it
+            self.skip_whitespace();
+            Err(self.error("Unexpected extra content"))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::parser::{Parser, Whitespace};
+
+    #[test]
+    fn test_many() {
+        let mut parser = Parser::new("1 2 3 x");
+
+        assert_eq!(parser.many(|p| p.usize()), Ok(vec![1, 2, 3]));
+        assert_eq!(parser.many(|p| p.usize()), Ok(vec![]));
+        assert!(parser.many1(|p| p.usize()).is_err());
+        assert_eq!(parser.many1(|p| p.literal("x")), Ok(vec![()]));
+        assert!(parser.is_exhausted());
+
+        // A partial first match is undone, so whatever comes next can still match from the start
+        let mut parser = Parser::new("a c");
+        assert!(parser.many1(|p| p.literal("a").and_then(|_| p.literal("b"))).is_err());
+        assert_eq!(parser.many1(|p| p.alt(&[&|p| p.literal("a"), &|p| p.literal("c")])), Ok(vec![(), ()]));
+
+        // A parser that doesn't consume anything would repeat forever
+        assert!(Parser::new("1").many(|p| p.optional(|p| p.literal("-"))).is_err());
+    }
+
+    #[test]
+    fn test_sep_by() {
+        assert_eq!(Parser::new("1,2, 3").sep_by(|p| p.usize(), |p| p.literal(",")), Ok(vec![1, 2, 3]));
+        assert_eq!(Parser::new("").sep_by(|p| p.usize(), |p| p.literal(",")), Ok(vec![]));
+        assert!(Parser::new("1,2,").sep_by(|p| p.usize(), |p| p.literal(",")).is_err());
+
+        let mut parser = Parser::new("1,2;3");
+        assert_eq!(parser.sep_by(|p| p.usize(), |p| p.literal(",")), Ok(vec![1, 2]));
+        assert!(parser.literal(";").is_ok());
+    }
+
+    #[test]
+    fn test_optional() {
+        let mut parser = Parser::new("-5 10");
+
+        assert_eq!(parser.optional(|p| p.literal("-")), Ok(Some(())));
+        assert_eq!(parser.usize(), Ok(5));
+        assert_eq!(parser.optional(|p| p.literal("-")), Ok(None));
+        assert_eq!(parser.usize(), Ok(10));
+    }
+
+    #[test]
+    fn test_delimited_and_terminated() {
+        let mut parser = Parser::new("(1,2) {3};");
+
+        let wires = parser.delimited(|p| p.literal("("), |p| p.sep_by(|p| p.usize(), |p| p.literal(",")), |p| p.literal(")"));
+        assert_eq!(wires, Ok(vec![1, 2]));
+
+        let joltage = parser.terminated(|p| p.delimited(|p| p.literal("{"), |p| p.usize(), |p| p.literal("}")), |p| p.literal(";"));
+        assert_eq!(joltage, Ok(3));
+
+        assert!(Parser::new("(1,2}").delimited(|p| p.literal("("), |p| p.sep_by(|p| p.usize(), |p| p.literal(",")), |p| p.literal(")")).is_err());
+    }
+
+    #[test]
+    fn test_alt() {
+        let mut parser = Parser::new("# 12 .");
+        let cell = |p: &mut Parser| p.alt(&[
+            &|p| p.literal("#").map(|_| 1),
+            &|p| p.literal(".").map(|_| 0),
+            &|p| p.usize(),
+        ]);
+
+        assert_eq!(parser.repeat(3, cell), Ok(vec![1, 12, 0]));
+        assert!(Parser::new("x").alt(&[&|p| p.literal("#"), &|p| p.literal(".")]).is_err());
+    }
+
+    #[test]
+    fn test_repeat() {
+        assert_eq!(Parser::new("1 2 3 4").repeat(3, |p| p.usize()), Ok(vec![1, 2, 3]));
+        assert!(Parser::new("1 2").repeat(3, |p| p.usize()).is_err());
+    }
+
+    #[test]
+    fn test_number() {
+        let mut parser = Parser::new("-128 +7 340282366920938463463374607431768211455 -9223372036854775808");
+
+        assert_eq!(parser.number::<i8>(), Ok(-128));
+        assert_eq!(parser.number::<u8>(), Ok(7));
+        assert_eq!(parser.number::<u128>(), Ok(u128::MAX));
+        assert_eq!(parser.number::<i64>(), Ok(i64::MIN));
+        assert!(parser.is_exhausted());
+
+        assert!(Parser::new("256").number::<u8>().is_err());
+        assert!(Parser::new("-129").number::<i8>().is_err());
+        assert!(Parser::new("-1").number::<usize>().is_err());
+        let mut parser = Parser::new("- 1");
+        assert!(parser.number::<isize>().is_err());
+        assert_eq!(parser.literal("-"), Ok(()));
+        assert!(Parser::new("x").number::<u32>().is_err());
+
+        // A failed number does not consume input
+        let mut parser = Parser::new("99999 1");
+        assert!(parser.number::<u16>().is_err());
+        assert_eq!(parser.number::<u32>(), Ok(99999));
+    }
+
+    #[test]
+    fn test_prefixed_number() {
+        let mut parser = Parser::new("0xff -0b101 0o17 0 0x 12");
+
+        assert_eq!(parser.prefixed_number::<u8>(), Ok(255));
+        assert_eq!(parser.prefixed_number::<i32>(), Ok(-5));
+        assert_eq!(parser.prefixed_number::<u64>(), Ok(15));
+        assert_eq!(parser.prefixed_number::<u64>(), Ok(0));
+        assert_eq!(parser.prefixed_number::<u64>(), Ok(0));
+        assert_eq!(parser.literal("x"), Ok(()));
+        assert_eq!(parser.prefixed_number::<u64>(), Ok(12));
+
+        assert!(Parser::new("0x100").prefixed_number::<u8>().is_err());
+        assert_eq!(Parser::new("0x10").number::<u8>(), Ok(0));
+    }
+
+    #[test]
+    fn test_whitespace_modes() {
+        let mut parser = Parser::new("1 2\n3").with_whitespace(Whitespace::Spaces);
+        assert_eq!(parser.many(|p| p.usize()), Ok(vec![1, 2]));
+        assert_eq!(parser.newline(), Ok(()));
+        assert_eq!(parser.usize(), Ok(3));
+
+        let mut parser = Parser::new(" 1 \r\n2").with_whitespace(Whitespace::None);
+        assert!(parser.usize().is_err());
+        assert_eq!(parser.space(), Ok(()));
+        assert_eq!(parser.column(), 1);
+        assert_eq!(parser.usize(), Ok(1));
+        assert!(parser.newline().is_err());
+        assert_eq!(parser.space(), Ok(()));
+        assert_eq!(parser.newline(), Ok(()));
+        assert_eq!(parser.column(), 0);
+
+        parser.set_whitespace(Whitespace::All);
+        assert_eq!(parser.usize(), Ok(2));
+        assert!(parser.newline().is_err());
+        assert_eq!(Parser::new("1\n2").many(|p| p.usize()), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_scan() {
+        assert_eq!(scan!("40x12: 1 2 3", "{}x{}: {}"), Ok((40, 12, "1 2 3".to_string())));
+        assert_eq!(scan!("11-22", "{}-{}"), Ok((11u64, 22u64)));
+        assert_eq!(scan!("pos=<1,-2>", "pos=<{},{}>"), Ok((1, -2)));
+        assert_eq!(scan!("a", "{}"), Ok(('a',)));
+
+        let mut parser = Parser::new("1,2\n3,4\n");
+        assert_eq!(parser.scan("{},{}"), Ok((1, 2)));
+        assert_eq!(parser.scan("{},{}"), Ok((3, 4)));
+        assert!(parser.is_exhausted());
+    }
+
+    #[test]
+    fn test_scan_errors() {
+        assert_eq!(scan!("1x2", "{}x{}").map(|(w,): (usize,)| w).unwrap_err(), "Format '{}x{}' has 2 placeholders, but 1 values were requested");
+        assert!(scan!("12", "{}{}").map(|(_, _): (usize, usize)| ()).unwrap_err().contains("not followed by any text"));
+        assert!(scan!("1x2 extra", "{}x{} ").map(|(_, _): (usize, usize)| ()).unwrap_err().contains("Unexpected extra content"));
+
+        let error = scan!("40x-1: 5", "{}x{}: {}").map(|(_, _, _): (usize, usize, usize)| ()).unwrap_err();
+        assert!(error.starts_with("line 1, column 4: Placeholder 2 ('-1'): invalid digit"), "{}", error);
+
+        let error = scan!("40-12", "{}x{}").map(|(_, _): (usize, usize)| ()).unwrap_err();
+        assert!(error.starts_with("line 1, column 1: Placeholder 1: expected 'x' after it"), "{}", error);
+    }
+
+    #[test]
+    fn test_backtracking() {
+        let mut parser = Parser::new("  ab 12");
+
+        assert!(parser.literal("ac").is_err());
+        assert!(parser.one_of(vec!["ac", "b"]).is_err());
+        assert_eq!(parser.checkpoint(), Parser::new("").checkpoint());
+
+        let checkpoint = parser.checkpoint();
+        assert_eq!(parser.literal("a"), Ok(()));
+        parser.restore(checkpoint);
+
+        assert!(parser.try_parse(|p| { p.literal("a")?; p.literal("c") }).is_err());
+        assert_eq!(parser.peek_char(), Some('a'));
+        assert!(parser.lookahead(|p| p.str(2)));
+        assert!(!parser.lookahead(|p| p.usize()));
+
+        assert_eq!(parser.try_parse(|p| { p.literal("a")?; p.literal("b") }), Ok(()));
+        assert_eq!(parser.peek_char(), Some('1'));
+        assert_eq!(parser.usize(), Ok(12));
+        assert_eq!(parser.peek_char(), None);
+    }
+
+    #[test]
+    fn test_tokens() {
+        let mut parser = Parser::new("svr: fft dac_2 ->out\n  #.é# rest \nx");
+
+        assert_eq!(parser.identifier(), Ok("svr".to_string()));
+        assert!(parser.identifier().is_err());
+        assert_eq!(parser.char_in(":;"), Ok(':'));
+        assert_eq!(parser.word(), Ok("fft".to_string()));
+        assert_eq!(parser.identifier(), Ok("dac_2".to_string()));
+        assert_eq!(parser.take_until("out"), Ok("->".to_string()));
+        assert_eq!(parser.word(), Ok("out".to_string()));
+        assert_eq!(parser.take_while(|c| c == '#' || c == '.'), Ok("#.".to_string()));
+        assert_eq!(parser.take_while(|c| c == '#'), Ok("".to_string()));
+        assert!(parser.char_in("#.").is_err());
+        assert_eq!(parser.rest_of_line(), Ok("é# rest ".to_string()));
+        assert!(parser.take_until("y").is_err());
+        assert_eq!(parser.newline(), Ok(()));
+        assert_eq!(parser.rest_of_line(), Ok("x".to_string()));
+        assert!(parser.word().is_err());
+        assert!(parser.is_exhausted());
+    }
+
+    #[test]
+    fn test_multibyte_input() {
+        let mut parser = Parser::new("  é→ 12 ü\u{a0}x");
+
+        assert_eq!(parser.literal("é"), Ok(()));
+        assert_eq!(parser.str(1), Ok("→".to_string()));
+        assert_eq!(parser.usize(), Ok(12));
+        assert_eq!(parser.str(1), Ok("ü".to_string()));
+        assert_eq!(parser.literal("x"), Ok(()));
+        assert!(parser.is_exhausted());
+    }
+
+    #[test]
+    fn test_error_position() {
+        let mut parser = Parser::new("1,2\né,x\n");
+        assert!(parser.sep_by(|p| p.usize(), |p| p.literal(",")).is_ok());
+        assert!(parser.literal("é,").is_ok());
+
+        assert_eq!(parser.usize(), Err("line 2, column 3: Expected to find a number\n    é,x\n      ^".to_string()));
+        assert_eq!(parser.ensure_exhausted(), Err("line 2, column 3: Unexpected extra content\n    é,x\n      ^".to_string()));
+    }
+
+    #[test]
+    fn test_error_excerpt() {
+        let input = format!("{}x{}", "1,".repeat(50), "2,".repeat(50));
+        let mut parser = Parser::new(&input);
+        assert!(parser.sep_by(|p| p.usize(), |p| p.literal(",")).is_err());
+
+        let error = parser.usize().unwrap_err();
+        let lines = error.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "line 1, column 101: Expected to find a number");
+        assert_eq!(lines[1], format!("    ...{}x{}2", "1,".repeat(20), "2,".repeat(19)));
+        assert_eq!(lines[2].find('^'), lines[1].find('x'));
+    }
+}
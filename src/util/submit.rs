@@ -0,0 +1,452 @@
+use std::fmt;
+use std::fs::{read_to_string, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use regex::Regex;
+use crate::util::config;
+use crate::util::input::input_dir;
+use crate::util::number::{parse_u64, parse_usize, BigInt};
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    Wait,
+    AlreadySolved,
+    Unknown,
+}
+
+impl Verdict {
+    /// Whether the verdict tells us the submitted answer is definitely not the right one.
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Incorrect | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Incorrect => write!(f, "incorrect"),
+            Verdict::TooHigh => write!(f, "too-high"),
+            Verdict::TooLow => write!(f, "too-low"),
+            Verdict::Wait => write!(f, "wait"),
+            Verdict::AlreadySolved => write!(f, "already-solved"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "incorrect" => Ok(Verdict::Incorrect),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "wait" => Ok(Verdict::Wait),
+            "already-solved" => Ok(Verdict::AlreadySolved),
+            "unknown" => Ok(Verdict::Unknown),
+            _ => Err(format!("Unknown verdict: {}", s))
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Response {
+    pub verdict: Verdict,
+    pub message: String,
+}
+
+/// Turns the (HTML) body the answer endpoint returns into a verdict, keeping the human-readable
+/// message so things like the remaining wait time can be shown to the user.
+pub fn parse_response(body: &str) -> Response {
+    let article = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+    let tags = Regex::new(r"<[^>]*>").unwrap();
+    let whitespace = Regex::new(r"\s+").unwrap();
+
+    let content = article.captures(body).map(|c| c[1].to_string()).unwrap_or(body.to_string());
+    let message = whitespace.replace_all(&tags.replace_all(&content, ""), " ").trim().to_string();
+
+    let verdict = if message.contains("That's the right answer") {
+        Verdict::Correct
+    } else if message.contains("You gave an answer too recently") {
+        Verdict::Wait
+    } else if message.contains("your answer is too high") {
+        Verdict::TooHigh
+    } else if message.contains("your answer is too low") {
+        Verdict::TooLow
+    } else if message.contains("That's not the right answer") {
+        Verdict::Incorrect
+    } else if message.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown
+    };
+
+    Response { verdict, message }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Attempt {
+    pub timestamp: u64,
+    pub day: usize,
+    pub part: usize,
+    /// Fingerprint of the input the answer was computed for; inputs differ per account.
+    pub input: String,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}\t{}\t{}\t{}\t{}", self.timestamp, self.day, self.part, self.input, self.verdict, self.answer)
+    }
+}
+
+impl FromStr for Attempt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.splitn(6, '\t').collect::<Vec<_>>();
+        if parts.len() != 6 {
+            return Err(format!("Invalid ledger line: '{}'", s));
+        }
+
+        Ok(Attempt {
+            timestamp: parse_u64(parts[0])?,
+            day: parse_usize(parts[1])?,
+            part: parse_usize(parts[2])?,
+            input: parts[3].to_string(),
+            verdict: parts[4].parse()?,
+            answer: parts[5].to_string(),
+        })
+    }
+}
+
+/// Local record of every answer we ever submitted, so we don't waste attempts (and wait time) on
+/// answers the server already told us are wrong.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct Ledger {
+    path: PathBuf,
+    pub attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Ledger { path, attempts: vec![] });
+        }
+
+        let content = read_to_string(&path).map_err(|e| format!("Could not read answer ledger {:?}: {}", path, e))?;
+        let attempts = content.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.parse())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Ledger { path, attempts })
+    }
+
+    pub fn attempts_for(&self, day: usize, part: usize, input: &str) -> Vec<&Attempt> {
+        self.attempts.iter().filter(|a| a.day == day && a.part == part && a.input == input).collect()
+    }
+
+    /// The answer the server accepted for this day, part and input, if any.
+    pub fn correct_answer(&self, day: usize, part: usize, input: &str) -> Option<&str> {
+        self.attempts_for(day, part, input).iter()
+            .find(|a| a.verdict == Verdict::Correct)
+            .map(|a| a.answer.as_str())
+    }
+
+    /// Checks whether submitting the given answer could possibly be useful, based on earlier attempts
+    /// for the same input.
+    pub fn check(&self, day: usize, part: usize, input: &str, answer: &str) -> Result<(), String> {
+        check_answer(answer)?;
+
+        let attempts = self.attempts_for(day, part, input);
+
+        if let Some(correct) = attempts.iter().find(|a| a.verdict == Verdict::Correct) {
+            return if correct.answer == answer {
+                Err(format!("Answer {} for day {} part {} was already accepted.", answer, day, part))
+            } else {
+                Err(format!("Day {} part {} was already solved with answer {}, refusing to submit {}.", day, part, correct.answer, answer))
+            };
+        }
+
+        if let Some(wrong) = attempts.iter().find(|a| a.answer == answer && a.verdict.is_wrong()) {
+            return Err(format!("Answer {} for day {} part {} was already submitted and was {}.", answer, day, part, wrong.verdict));
+        }
+
+        // Numeric answers (of any size) can be checked against the too high/too low bounds we learned before.
+        let Ok(value) = answer.parse::<BigInt>() else { return Ok(()) };
+        let numeric = |verdict: Verdict| attempts.iter()
+            .filter(move |a| a.verdict == verdict)
+            .filter_map(|a| a.answer.parse::<BigInt>().ok());
+
+        if let Some(high) = numeric(Verdict::TooHigh).min() && value >= high {
+            return Err(format!("Answer {} for day {} part {} is out of range: {} was already too high.", answer, day, part, high));
+        }
+        if let Some(low) = numeric(Verdict::TooLow).max() && value <= low {
+            return Err(format!("Answer {} for day {} part {} is out of range: {} was already too low.", answer, day, part, low));
+        }
+
+        Ok(())
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), String> {
+        check_answer(&attempt.answer)?;
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)
+            .map_err(|e| format!("Could not open answer ledger {:?}: {}", self.path, e))?;
+        writeln!(file, "{}", attempt).map_err(|e| format!("Could not write answer ledger {:?}: {}", self.path, e))?;
+
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+/// The ledger has one tab separated line per attempt, so answers can't contain tabs or newlines.
+fn check_answer(answer: &str) -> Result<(), String> {
+    if answer.contains(['\t', '\n', '\r']) {
+        return Err(format!("Answer {:?} contains tabs or newlines, which cannot be submitted.", answer));
+    }
+
+    Ok(())
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct SubmitSettings {
+    /// Endpoint to post answers to; `{day}` is replaced with the day number.
+    pub endpoint: String,
+    pub session: Option<String>,
+    pub ledger: PathBuf,
+    pub timeout: Duration,
+}
+
+impl SubmitSettings {
+    pub fn from_config() -> Result<Self, String> {
+        let config = config::current();
+
+        Ok(SubmitSettings {
+            endpoint: config.submit_url.replace("{year}", &config.year.to_string()),
+            session: config.session()?,
+            ledger: ledger_path(),
+            timeout: Duration::from_secs(config.timeout),
+        })
+    }
+}
+
+pub fn ledger_path() -> PathBuf {
+    input_dir().join("answers.ledger")
+}
+
+fn post_answer(settings: &SubmitSettings, day: usize, part: usize, answer: &str) -> Result<String, String> {
+    let url = settings.endpoint.replace("{day}", &day.to_string());
+    let agent = ureq::AgentBuilder::new().timeout(settings.timeout).build();
+
+    let mut request = agent.post(&url)
+        .set("User-Agent", "github.com/fvanderveen/advent-of-code-2025");
+    if let Some(session) = &settings.session {
+        request = request.set("Cookie", &format!("session={}", session));
+    }
+
+    match request.send_form(&[("level", &part.to_string()), ("answer", answer)]) {
+        Ok(response) => response.into_string().map_err(|e| format!("Could not read response from {}: {}", url, e)),
+        Err(ureq::Error::Status(code, _)) => Err(format!("Submitting to {} failed with status {}", url, code)),
+        Err(e) => Err(format!("Submitting to {} failed: {}", url, e)),
+    }
+}
+
+/// Submits an answer, unless the ledger already knows it cannot be right, and records the attempt.
+pub fn submit(settings: &SubmitSettings, day: usize, part: usize, input: &str, answer: &str) -> Result<Response, String> {
+    if part != 1 && part != 2 {
+        return Err(format!("Invalid part {}, expected 1 or 2", part));
+    }
+
+    let mut ledger = Ledger::load(settings.ledger.clone())?;
+    ledger.check(day, part, input, answer)?;
+
+    let response = parse_response(&post_answer(settings, day, part, answer)?);
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    ledger.record(Attempt { timestamp, day, part, input: input.to_string(), verdict: response.verdict, answer: answer.to_string() })?;
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::sync::mpsc::{channel, Receiver};
+    use std::thread;
+    use std::time::Duration;
+    use crate::util::number::parse_usize;
+    use crate::util::submit::{parse_response, submit, Attempt, Ledger, SubmitSettings, Verdict};
+
+    const CORRECT_BODY: &str = "<html><main><article><p>That's the right answer!  You are <span>one gold star</span> closer.</p></article></main></html>";
+    const TOO_HIGH_BODY: &str = "<html><main><article><p>That's not the right answer; your answer is too high.  Please wait one minute.</p></article></main></html>";
+    const TOO_LOW_BODY: &str = "<html><main><article><p>That's not the right answer; your answer is too low.</p></article></main></html>";
+    const WRONG_BODY: &str = "<html><main><article><p>That's not the right answer.  If you're stuck, make sure you're using the full input data.</p></article></main></html>";
+    const WAIT_BODY: &str = "<html><main><article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 34s left to wait.</p></article></main></html>";
+    const SOLVED_BODY: &str = "<html><main><article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article></main></html>";
+
+    fn attempt(day: usize, part: usize, verdict: Verdict, answer: &str) -> Attempt {
+        Attempt { timestamp: 0, day, part, input: "input".to_string(), verdict, answer: answer.to_string() }
+    }
+
+    fn ledger(attempts: Vec<Attempt>) -> Ledger {
+        Ledger { path: PathBuf::new(), attempts }
+    }
+
+    fn temp_ledger(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aoc-{}-{}.ledger", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// Starts a stand-in for the answer endpoint, replying to a single request with the given body.
+    fn serve_once(body: &'static str) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, receiver) = channel();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = parse_usize(length.trim()).unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" { break; }
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+
+            let mut stream = reader.into_inner();
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).unwrap();
+            sender.send(request).unwrap();
+        });
+
+        (format!("http://{}/2025/day/{{day}}/answer", address), receiver)
+    }
+
+    fn settings(endpoint: String, ledger: PathBuf) -> SubmitSettings {
+        SubmitSettings { endpoint, session: Some("cookie".to_string()), ledger, timeout: Duration::from_secs(5) }
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(parse_response(CORRECT_BODY).verdict, Verdict::Correct);
+        assert_eq!(parse_response(TOO_HIGH_BODY).verdict, Verdict::TooHigh);
+        assert_eq!(parse_response(TOO_LOW_BODY).verdict, Verdict::TooLow);
+        assert_eq!(parse_response(WRONG_BODY).verdict, Verdict::Incorrect);
+        assert_eq!(parse_response(WAIT_BODY).verdict, Verdict::Wait);
+        assert_eq!(parse_response(SOLVED_BODY).verdict, Verdict::AlreadySolved);
+        assert_eq!(parse_response("<html>Puzzle inputs differ by user.</html>").verdict, Verdict::Unknown);
+
+        assert_eq!(parse_response(CORRECT_BODY).message, "That's the right answer! You are one gold star closer.");
+    }
+
+    #[test]
+    fn test_attempt_round_trip() {
+        let attempt = Attempt { timestamp: 1764547200, day: 3, part: 2, input: "af63dc4c8601ec8c".to_string(), verdict: Verdict::TooLow, answer: "1234".to_string() };
+
+        assert_eq!(attempt.to_string(), "1764547200\t3\t2\taf63dc4c8601ec8c\ttoo-low\t1234");
+        assert_eq!(attempt.to_string().parse(), Ok(attempt));
+    }
+
+    #[test]
+    fn test_ledger_check() {
+        let ledger = ledger(vec![
+            attempt(1, 1, Verdict::Incorrect, "abc"),
+            attempt(1, 1, Verdict::TooHigh, "500"),
+            attempt(1, 1, Verdict::TooHigh, "700"),
+            attempt(1, 1, Verdict::TooLow, "100"),
+            attempt(1, 1, Verdict::Wait, "300"),
+            attempt(1, 2, Verdict::Correct, "42"),
+            attempt(3, 1, Verdict::TooLow, "340282366920938463463374607431768211456"),
+        ]);
+
+        assert!(ledger.check(1, 1, "input", "abc").is_err());
+        assert!(ledger.check(1, 1, "input", "500").is_err());
+        assert!(ledger.check(1, 1, "input", "600").is_err());
+        assert!(ledger.check(1, 1, "input", "100").is_err());
+        assert!(ledger.check(1, 1, "input", "-5").is_err());
+        assert!(ledger.check(1, 1, "input", "300").is_ok());
+        assert!(ledger.check(1, 1, "input", "499").is_ok());
+        assert!(ledger.check(1, 1, "input", "def").is_ok());
+
+        assert!(ledger.check(1, 2, "input", "42").is_err());
+        assert!(ledger.check(1, 2, "input", "43").is_err());
+        assert_eq!(ledger.correct_answer(1, 2, "input"), Some("42"));
+
+        // A teammate's input has its own history
+        assert!(ledger.check(1, 1, "other-input", "500").is_ok());
+        assert_eq!(ledger.correct_answer(1, 2, "other-input"), None);
+
+        // Other days are unaffected
+        assert!(ledger.check(2, 1, "input", "500").is_ok());
+
+        // Bounds also apply to answers that don't fit in a primitive
+        assert!(ledger.check(3, 1, "input", "340282366920938463463374607431768211455").is_err());
+        assert!(ledger.check(3, 1, "input", "340282366920938463463374607431768211457").is_ok());
+    }
+
+    #[test]
+    fn test_ledger_rejects_multi_line_answers() {
+        let path = temp_ledger("multi-line");
+        let mut ledger = Ledger::load(path.clone()).unwrap();
+
+        assert!(ledger.record(attempt(1, 1, Verdict::Incorrect, "12\n34")).is_err());
+        assert!(ledger.record(attempt(1, 1, Verdict::Incorrect, "12\t34")).is_err());
+        assert!(ledger.check(1, 1, "input", "12\n34").is_err());
+        assert!(ledger.record(attempt(1, 1, Verdict::Incorrect, "1234")).is_ok());
+
+        let ledger = Ledger::load(path.clone());
+        assert_eq!(ledger.map(|l| l.attempts), Ok(vec![attempt(1, 1, Verdict::Incorrect, "1234")]));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_submit() {
+        let path = temp_ledger("submit");
+        let (endpoint, requests) = serve_once(TOO_HIGH_BODY);
+
+        let response = submit(&settings(endpoint.clone(), path.clone()), 7, 2, "input", "1000");
+        assert_eq!(response.map(|r| r.verdict), Ok(Verdict::TooHigh));
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2025/day/7/answer "), "{}", request);
+        assert!(request.contains("session=cookie"), "{}", request);
+        assert!(request.ends_with("level=2&answer=1000"), "{}", request);
+
+        let ledger = Ledger::load(path.clone()).unwrap();
+        assert_eq!(ledger.attempts.len(), 1);
+        assert_eq!(ledger.attempts[0].verdict, Verdict::TooHigh);
+
+        // Anything at or above a too high answer is rejected without contacting the server.
+        assert!(submit(&settings(endpoint, path.clone()), 7, 2, "input", "1001").is_err());
+
+        let (endpoint, _) = serve_once(CORRECT_BODY);
+        let response = submit(&settings(endpoint, path.clone()), 7, 2, "input", "999");
+        assert_eq!(response.map(|r| r.verdict), Ok(Verdict::Correct));
+        assert_eq!(Ledger::load(path.clone()).unwrap().attempts.len(), 2);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}